@@ -1,12 +1,24 @@
+use std::cmp::Reverse;
+use std::collections::{hash_map::Entry, BTreeMap, HashMap, HashSet};
 use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
 #[allow(unused_imports, unused_variables)]
 use std::{env, path::PathBuf, str::FromStr, time};
 
 use bitcoincore_rpc::{
-    bitcoin::{block, Network},
+    bitcoin::consensus::encode,
+    bitcoin::hex::{FromHex, HexToBytesError},
+    bitcoin::{
+        block, transaction, Address, BlockHash, CompactTarget, Network, OutPoint, Script,
+        ScriptBuf, Sequence, Target, Transaction, TxIn, TxOut, Txid,
+    },
+    json::BlockStatsFields,
     Auth, Client, Error as BitcoinRpcError, RpcApi,
 };
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
 #[macro_use]
 extern crate lazy_static;
 
@@ -16,6 +28,7 @@ trait LoadCredentials {
     fn from_env() -> Self;
 }
 
+#[allow(dead_code)]
 struct RpcCredentials {
     rpc_url: String,
     rpc_user: String,
@@ -88,6 +101,25 @@ lazy_static! {
     };
 }
 
+lazy_static! {
+    /**
+     * Well known mainnet addresses nobody holds the keys to
+     */
+    static ref BURN_SCRIPTS: Vec<ScriptBuf> = [
+        "1111111111111111111114oLvT2",
+        "1BitcoinEaterAddressDontSendf59kuE",
+        "1CounterpartyXXXXXXXXXXXXXXXUWLpVr",
+    ]
+    .iter()
+    .map(|address| {
+        Address::from_str(address)
+            .expect("invalid burn address")
+            .assume_checked()
+            .script_pubkey()
+    })
+    .collect();
+}
+
 fn get_block_by_height(block_height: u64) -> Result<block::Block> {
     let rpc = &*RPC_CLIENT;
     let block = rpc.get_block_hash(block_height)?;
@@ -95,6 +127,17 @@ fn get_block_by_height(block_height: u64) -> Result<block::Block> {
     Ok(block)
 }
 
+fn blocks_in_range(start: u64, end: u64) -> impl Iterator<Item = Result<(u64, block::Block)>> {
+    (start..=end).map(|height| Ok((height, get_block_by_height(height)?)))
+}
+
+fn check_range(start: u64, end: u64) -> std::result::Result<(), Box<dyn Error>> {
+    if start > end {
+        return Err(format!("start height {} is after end height {}", start, end).into());
+    }
+    Ok(())
+}
+
 fn get_block_time(block_height: u64) -> Result<Duration> {
     let block = get_block_by_height(block_height)?;
     Ok(Duration::seconds(block.header.time as i64))
@@ -145,106 +188,5154 @@ pub fn get_chain() -> Result<Network> {
     Ok(chain.chain)
 }
 
-use clap::{Parser, Subcommand};
+/**
+ * Bytes of witness data in a transaction, including the segwit marker and flag
+ */
+fn witness_size(tx: &Transaction) -> usize {
+    tx.total_size() - tx.base_size()
+}
 
-#[derive(Parser)]
-#[command(name = "bitcoin-rpc-cli")]
-#[command(version = "0.1.0")]
-#[command(about = "A CLI for the Bitcoin RPC API", long_about = None)]
-struct Cli {
-    #[command(subcommand)]
-    command: Option<Commands>,
+#[derive(Debug, Default, PartialEq)]
+pub struct SegwitSavings {
+    pub transactions: u64,
+    pub total_weight: u64,
+    pub saved_weight: u64,
 }
 
-#[derive(Subcommand)]
-enum Commands {
-    #[command(about = "Get the current chain")]
-    Chain,
-    #[command(about = "Get the it took to mine a block")]
-    TimeToMine {
-        #[arg(required = true, help = "(numeric, required) The height index")]
-        block_height: u64,
-    },
-    #[command(about = "Get the number of transactions in a block")]
-    NumberOfTransactions {
-        #[arg(required = true, help = "(numeric, required) The height index")]
-        block_height: u64,
-    },
-    #[command(about = "Guess how long until next block is mined")]
-    NextBlock,
+impl SegwitSavings {
+    /**
+     * Share of the weight the blocks would have had without the witness discount that was saved
+     */
+    pub fn saved_ratio(&self) -> f64 {
+        let undiscounted = self.total_weight + self.saved_weight;
+        if undiscounted == 0 {
+            return 0.0;
+        }
+        self.saved_weight as f64 / undiscounted as f64
+    }
+
+    /**
+     * How many more average sized transactions would fit in the saved weight
+     */
+    pub fn extra_transactions(&self) -> f64 {
+        if self.transactions == 0 {
+            return 0.0;
+        }
+        let avg_tx_weight = self.total_weight as f64 / self.transactions as f64;
+        self.saved_weight as f64 / avg_tx_weight
+    }
 }
 
-// QUESTION: is this the best way to make error handling happen in a single place?
-// if command returns an error then return it to whatever called `call_command`
-fn call_command(command: Commands) -> std::result::Result<(), Box<dyn Error>> {
-    match command {
-        Commands::Chain => {
-            let chain = get_chain()?;
-            println!("{}", chain);
+/**
+ * Witness bytes count one weight unit instead of four, so every witness byte saves three
+ */
+fn segwit_savings<I>(blocks: I) -> Result<SegwitSavings>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    let mut savings = SegwitSavings::default();
+    for block in blocks {
+        let (_, block) = block?;
+        savings.transactions += block.txdata.len() as u64;
+        savings.total_weight += block.weight().to_wu();
+        savings.saved_weight += block
+            .txdata
+            .iter()
+            .map(|tx| witness_size(tx) as u64 * 3)
+            .sum::<u64>();
+    }
+    Ok(savings)
+}
+
+pub fn range_segwit_savings(start: u64, end: u64) -> Result<SegwitSavings> {
+    segwit_savings(blocks_in_range(start, end))
+}
+
+/**
+ * Finds txids included in more than one block, which was possible before BIP30
+ */
+fn duplicate_txids<I>(blocks: I) -> Result<Vec<(Txid, Vec<u64>)>>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    let mut heights: HashMap<Txid, Vec<u64>> = HashMap::new();
+    for block in blocks {
+        let (height, block) = block?;
+        for tx in &block.txdata {
+            heights.entry(tx.compute_txid()).or_default().push(height);
         }
-        Commands::TimeToMine { block_height } => {
-            let time = time_to_mine(block_height)?;
-            println!("{}s, {}min", time.num_seconds(), time.num_minutes());
+    }
+
+    let mut duplicates: Vec<(Txid, Vec<u64>)> = heights
+        .into_iter()
+        .filter(|(_, heights)| heights.len() > 1)
+        .collect();
+    duplicates.sort_by_key(|(_, heights)| heights[0]);
+    Ok(duplicates)
+}
+
+pub fn range_duplicate_txids(start: u64, end: u64) -> Result<Vec<(Txid, Vec<u64>)>> {
+    duplicate_txids(blocks_in_range(start, end))
+}
+
+#[derive(Clone, Debug)]
+struct Prevout {
+    txout: TxOut,
+    height: u64,
+    time: u32,
+}
+
+trait PrevoutSource {
+    fn prevout(&mut self, outpoint: &OutPoint) -> Result<Prevout>;
+}
+
+trait TransactionSource {
+    fn transaction(&mut self, txid: &Txid) -> Result<Transaction>;
+}
+
+/**
+ * Looks up spent outputs with `getrawtransaction`, so the node needs `-txindex`.
+ * Transactions and block heights are cached so inputs spending the same parent only cost one call
+ */
+#[derive(Default)]
+struct RpcPrevouts {
+    txs: HashMap<Txid, (Transaction, u64, u32)>,
+    heights: HashMap<BlockHash, u64>,
+}
+
+impl RpcPrevouts {
+    fn lookup(&mut self, txid: &Txid) -> Result<&(Transaction, u64, u32)> {
+        match self.txs.entry(*txid) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let rpc = &*RPC_CLIENT;
+                let info = rpc.get_raw_transaction_info(txid, None)?;
+                let block_hash = info.blockhash.ok_or(BitcoinRpcError::UnexpectedStructure)?;
+                let height = match self.heights.entry(block_hash) {
+                    Entry::Occupied(height) => *height.get(),
+                    Entry::Vacant(height) => {
+                        *height.insert(rpc.get_block_header_info(&block_hash)?.height as u64)
+                    }
+                };
+                let time = info.blocktime.ok_or(BitcoinRpcError::UnexpectedStructure)? as u32;
+                let tx = info
+                    .transaction()
+                    .map_err(|_| BitcoinRpcError::UnexpectedStructure)?;
+                Ok(entry.insert((tx, height, time)))
+            }
         }
-        Commands::NumberOfTransactions { block_height } => {
-            let num = number_of_transactions(block_height)?;
-            println!("{} transactions", num);
+    }
+}
+
+impl PrevoutSource for RpcPrevouts {
+    fn prevout(&mut self, outpoint: &OutPoint) -> Result<Prevout> {
+        let (tx, height, time) = self.lookup(&outpoint.txid)?;
+        let txout = tx
+            .output
+            .get(outpoint.vout as usize)
+            .ok_or(BitcoinRpcError::UnexpectedStructure)?;
+        Ok(Prevout {
+            txout: txout.clone(),
+            height: *height,
+            time: *time,
+        })
+    }
+}
+
+impl TransactionSource for RpcPrevouts {
+    fn transaction(&mut self, txid: &Txid) -> Result<Transaction> {
+        Ok(self.lookup(txid)?.0.clone())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TxFee {
+    pub txid: Txid,
+    pub fee: u64,
+    pub vsize: u64,
+}
+
+impl TxFee {
+    /**
+     * Fee rate in sat/vB
+     */
+    pub fn fee_rate(&self) -> f64 {
+        self.fee as f64 / self.vsize as f64
+    }
+}
+
+/**
+ * Total value of a transaction's outputs, in sats
+ */
+fn output_value(tx: &Transaction) -> u64 {
+    tx.output.iter().map(|out| out.value.to_sat()).sum()
+}
+
+fn tx_fee(tx: &Transaction, prevouts: &mut impl PrevoutSource) -> Result<TxFee> {
+    let mut input_value: u64 = 0;
+    for input in &tx.input {
+        input_value += prevouts
+            .prevout(&input.previous_output)?
+            .txout
+            .value
+            .to_sat();
+    }
+    Ok(TxFee {
+        txid: tx.compute_txid(),
+        fee: input_value.saturating_sub(output_value(tx)),
+        vsize: tx.vsize() as u64,
+    })
+}
+
+/**
+ * Fees of every transaction in a block except the coinbase
+ */
+fn block_fees(block: &block::Block, prevouts: &mut impl PrevoutSource) -> Result<Vec<TxFee>> {
+    block
+        .txdata
+        .iter()
+        .filter(|tx| !tx.is_coinbase())
+        .map(|tx| tx_fee(tx, prevouts))
+        .collect()
+}
+
+/**
+ * The fee rate at which half of the block's vsize paid less and half paid more,
+ * the same "median feerate" `getblockstats` reports
+ */
+fn weighted_median_fee_rate(fees: &[TxFee]) -> Option<f64> {
+    let mut fees: Vec<&TxFee> = fees.iter().collect();
+    fees.sort_by(|a, b| a.fee_rate().total_cmp(&b.fee_rate()));
+
+    let total_vsize: u64 = fees.iter().map(|fee| fee.vsize).sum();
+    let mut cumulative_vsize = 0;
+    for fee in fees {
+        cumulative_vsize += fee.vsize;
+        if cumulative_vsize * 2 >= total_vsize {
+            return Some(fee.fee_rate());
         }
-        Commands::NextBlock => {
-            println!("Next block will be mined in: ");
-            let time = guess_time_to_mine_next_block()?;
-            println!(
-                "{}s, {}min, {}days",
-                time.num_seconds(),
-                time.num_minutes(),
-                time.num_days()
-            );
+    }
+    None
+}
+
+pub fn weighted_median_fee(block_height: u64) -> Result<Option<f64>> {
+    let block = get_block_by_height(block_height)?;
+    let fees = block_fees(&block, &mut RpcPrevouts::default())?;
+    Ok(weighted_median_fee_rate(&fees))
+}
+
+const HALVING_INTERVAL: u64 = 210_000;
+
+/**
+ * New coins issued by a block at the given height, in sats
+ */
+pub fn block_subsidy(block_height: u64) -> u64 {
+    let halvings = block_height / HALVING_INTERVAL;
+    if halvings >= 64 {
+        return 0;
+    }
+    (50 * 100_000_000) >> halvings
+}
+
+/**
+ * Every coin issued up to and including the block at the given height, in sats. This is the
+ * schedule's supply, it doesn't account for the unspendable genesis output or coinbases that
+ * claimed less than they could
+ */
+pub fn cumulative_supply(block_height: u64) -> u64 {
+    let mut supply = 0;
+    let mut era_start = 0;
+    while era_start <= block_height {
+        let subsidy = block_subsidy(era_start);
+        if subsidy == 0 {
+            break;
         }
-    };
-    Ok(())
+        let blocks = (block_height - era_start + 1).min(HALVING_INTERVAL);
+        supply += subsidy * blocks;
+        era_start += HALVING_INTERVAL;
+    }
+    supply
 }
 
-fn main() {
-    let cli: Cli = Cli::parse();
+const BLOCKS_PER_YEAR: u64 = 52_560;
 
-    if let Err(e) = match cli.command {
-        Some(cmd) => call_command(cmd),
-        None => {
-            eprintln!("No command provided");
-            Ok(())
+/**
+ * A year of blocks at the height's subsidy as a percentage of the supply at that height
+ */
+pub fn inflation_rate(block_height: u64) -> f64 {
+    let yearly = block_subsidy(block_height) * BLOCKS_PER_YEAR;
+    yearly as f64 / cumulative_supply(block_height) as f64 * 100.0
+}
+
+/**
+ * Sum over every input of its value in BTC times the days since the spent output was mined
+ */
+fn coin_days_destroyed(block: &block::Block, prevouts: &mut impl PrevoutSource) -> Result<f64> {
+    let mut cdd = 0.0;
+    for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+        for input in &tx.input {
+            let prevout = prevouts.prevout(&input.previous_output)?;
+            let age_secs = (block.header.time as i64 - prevout.time as i64).max(0);
+            cdd += prevout.txout.value.to_btc() * age_secs as f64 / 86_400.0;
         }
-    } {
-        eprintln!("Error: {}", e);
     }
+    Ok(cdd)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/**
+ * Coin days destroyed per BTC of subsidy, `None` once the subsidy has run out
+ */
+fn cdd_per_subsidy(cdd: f64, subsidy: u64) -> Option<f64> {
+    if subsidy == 0 {
+        return None;
+    }
+    Some(cdd / (subsidy as f64 / 100_000_000.0))
+}
 
-    #[test]
-    fn test_get_chain() {
-        // QUESTION: how to test just that this returns instance of Network? Or is that redundant because of type safety. What would be a better test if so?
-        let chain = get_chain().unwrap();
-        match chain {
-            Network::Bitcoin | Network::Regtest | Network::Signet | Network::Testnet => {
-                println!("{}", chain);
+pub fn cdd_ratio(block_height: u64) -> Result<(f64, Option<f64>)> {
+    let block = get_block_by_height(block_height)?;
+    let cdd = coin_days_destroyed(&block, &mut RpcPrevouts::default())?;
+    Ok((cdd, cdd_per_subsidy(cdd, block_subsidy(block_height))))
+}
+
+/**
+ * Average number of blocks between a transaction being mined and one of its outputs first
+ * being spent, over the non-coinbase transactions of `block` that get spent in `later_blocks`
+ */
+fn child_spacing<I>(block: &block::Block, block_height: u64, later_blocks: I) -> Result<Option<f64>>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    let mut unspent: HashSet<Txid> = block
+        .txdata
+        .iter()
+        .filter(|tx| !tx.is_coinbase())
+        .map(|tx| tx.compute_txid())
+        .collect();
+    let mut spacings = Vec::new();
+
+    for later in later_blocks {
+        if unspent.is_empty() {
+            break;
+        }
+        let (height, later) = later?;
+        for input in later.txdata.iter().flat_map(|tx| &tx.input) {
+            if unspent.remove(&input.previous_output.txid) {
+                spacings.push(height - block_height);
             }
-            _ => panic!("Unexpected network type"),
         }
     }
 
-    #[test]
-    fn test_time_to_mine() {
-        let time = time_to_mine(24).unwrap();
-        println!("{:?}", time);
-        assert_eq!(time.num_seconds(), 666);
+    if spacings.is_empty() {
+        return Ok(None);
     }
+    Ok(Some(
+        spacings.iter().sum::<u64>() as f64 / spacings.len() as f64,
+    ))
+}
 
-    #[test]
-    fn test_num_transactions() {
-        let num = number_of_transactions(300_000).unwrap();
-        println!("{}", num);
-        assert_eq!(num, 237);
+/**
+ * Scans forward at most `max_depth` blocks, never past the tip
+ */
+pub fn block_child_spacing(block_height: u64, max_depth: u64) -> Result<Option<f64>> {
+    let rpc = &*RPC_CLIENT;
+    let tip = rpc.get_block_count()?;
+    let block = get_block_by_height(block_height)?;
+    let end = tip.min(block_height.saturating_add(max_depth));
+    child_spacing(&block, block_height, blocks_in_range(block_height + 1, end))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScriptType {
+    P2pk,
+    P2pkh,
+    P2sh,
+    P2wpkh,
+    P2wsh,
+    P2tr,
+    OpReturn,
+    Other,
+}
+
+impl ScriptType {
+    pub fn of(script: &Script) -> Self {
+        if script.is_p2pk() {
+            ScriptType::P2pk
+        } else if script.is_p2pkh() {
+            ScriptType::P2pkh
+        } else if script.is_p2sh() {
+            ScriptType::P2sh
+        } else if script.is_p2wpkh() {
+            ScriptType::P2wpkh
+        } else if script.is_p2wsh() {
+            ScriptType::P2wsh
+        } else if script.is_p2tr() {
+            ScriptType::P2tr
+        } else if script.is_op_return() {
+            ScriptType::OpReturn
+        } else {
+            ScriptType::Other
+        }
+    }
+}
+
+impl FromStr for ScriptType {
+    type Err = String;
+
+    /**
+     * Accepts the variant names in any case, with `op_return` also accepted for OP_RETURN
+     */
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "p2pk" => Ok(ScriptType::P2pk),
+            "p2pkh" => Ok(ScriptType::P2pkh),
+            "p2sh" => Ok(ScriptType::P2sh),
+            "p2wpkh" => Ok(ScriptType::P2wpkh),
+            "p2wsh" => Ok(ScriptType::P2wsh),
+            "p2tr" => Ok(ScriptType::P2tr),
+            "opreturn" | "op_return" => Ok(ScriptType::OpReturn),
+            "other" => Ok(ScriptType::Other),
+            _ => Err(format!("unknown script type {}", s)),
+        }
+    }
+}
+
+/**
+ * An output is dust when it is worth less than the fee to spend it at the default dust relay fee
+ */
+fn is_dust(output: &TxOut) -> bool {
+    output.value < output.script_pubkey.minimal_non_dust()
+}
+
+/**
+ * Guesses which output sends money back to the spender. Wallets usually send change to the same
+ * script type they spend from, so when every input is the same type and exactly one of several
+ * outputs matches it, that output is assumed to be change. Anything else is left unclassified
+ */
+fn change_output(tx: &Transaction, prevouts: &mut impl PrevoutSource) -> Result<Option<usize>> {
+    if tx.is_coinbase() || tx.output.len() < 2 {
+        return Ok(None);
+    }
+
+    let mut input_types = HashSet::new();
+    for input in &tx.input {
+        let prevout = prevouts.prevout(&input.previous_output)?;
+        input_types.insert(ScriptType::of(&prevout.txout.script_pubkey));
+    }
+    let input_type = match input_types.into_iter().collect::<Vec<_>>()[..] {
+        [input_type] => input_type,
+        _ => return Ok(None),
+    };
+
+    let mut matching = tx
+        .output
+        .iter()
+        .enumerate()
+        .filter(|(_, output)| ScriptType::of(&output.script_pubkey) == input_type);
+    match (matching.next(), matching.next()) {
+        (Some((index, _)), None) => Ok(Some(index)),
+        _ => Ok(None),
+    }
+}
+
+/**
+ * Outputs of non-coinbase transactions that are neither OP_RETURN, dust nor likely change,
+ * returned as a count and total value in sats
+ */
+fn real_payments(block: &block::Block, prevouts: &mut impl PrevoutSource) -> Result<(u64, u64)> {
+    let (mut count, mut value) = (0, 0);
+    for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+        let change = change_output(tx, prevouts)?;
+        for (index, output) in tx.output.iter().enumerate() {
+            if Some(index) == change || output.script_pubkey.is_op_return() || is_dust(output) {
+                continue;
+            }
+            count += 1;
+            value += output.value.to_sat();
+        }
+    }
+    Ok((count, value))
+}
+
+pub fn block_real_payments(block_height: u64) -> Result<(u64, u64)> {
+    let block = get_block_by_height(block_height)?;
+    real_payments(&block, &mut RpcPrevouts::default())
+}
+
+/**
+ * Percent change from `first` to `last`
+ */
+fn percent_change(first: f64, last: f64) -> Option<f64> {
+    if first == 0.0 {
+        return None;
+    }
+    Some((last - first) / first * 100.0)
+}
+
+/**
+ * Average block size of each epoch, estimated from `samples` evenly spaced blocks per epoch
+ * so a long trend doesn't need a call for every block
+ */
+fn epoch_size_trend<F>(epochs: Range<u64>, samples: u64, mut size_at: F) -> Result<Vec<(u64, f64)>>
+where
+    F: FnMut(u64) -> Result<u64>,
+{
+    let samples = samples.clamp(1, 2016);
+    let mut averages = Vec::new();
+    for epoch in epochs {
+        let first_block = epoch * 2016;
+        let mut total = 0;
+        for i in 0..samples {
+            total += size_at(first_block + i * 2016 / samples)?;
+        }
+        averages.push((epoch, total as f64 / samples as f64));
+    }
+    Ok(averages)
+}
+
+/**
+ * Uses the `total_size` from `getblockstats`, which leaves out the coinbase
+ */
+pub fn recent_epoch_size_trend(epochs: u64, samples: u64) -> Result<Vec<(u64, f64)>> {
+    let rpc = &*RPC_CLIENT;
+    let current_epoch = rpc.get_block_count()? / 2016;
+    let first_epoch = current_epoch.saturating_sub(epochs);
+    epoch_size_trend(first_epoch..current_epoch, samples, |height| {
+        let stats = rpc.get_block_stats_fields(height, &[BlockStatsFields::TotalSize])?;
+        Ok(stats.total_size.unwrap_or_default() as u64)
+    })
+}
+
+/**
+ * Share of the block's fees paid by the `n` highest fee transactions
+ */
+fn top_fee_share(fees: &[TxFee], n: usize) -> Option<f64> {
+    let total: u64 = fees.iter().map(|fee| fee.fee).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut fees: Vec<u64> = fees.iter().map(|fee| fee.fee).collect();
+    fees.sort_unstable_by(|a, b| b.cmp(a));
+    let top: u64 = fees.iter().take(n).sum();
+    Some(top as f64 / total as f64)
+}
+
+pub fn block_top_fee_share(block_height: u64, n: usize) -> Result<Option<f64>> {
+    let block = get_block_by_height(block_height)?;
+    let fees = block_fees(&block, &mut RpcPrevouts::default())?;
+    Ok(top_fee_share(&fees, n))
+}
+
+#[derive(Debug)]
+pub enum DecodeTxError {
+    Hex(HexToBytesError),
+    Consensus(encode::Error),
+}
+
+/**
+ * rust-bitcoin keeps the detail of its errors in `source()`, so the whole chain is written out
+ */
+impl fmt::Display for DecodeTxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (kind, error): (&str, &dyn Error) = match self {
+            DecodeTxError::Hex(e) => ("invalid hex", e),
+            DecodeTxError::Consensus(e) => ("invalid transaction", e),
+        };
+        write!(f, "{}: {}", kind, error)?;
+        let mut source = error.source();
+        while let Some(e) = source {
+            write!(f, ": {}", e)?;
+            source = e.source();
+        }
+        Ok(())
+    }
+}
+
+impl Error for DecodeTxError {}
+
+/**
+ * Parses a raw transaction without asking the node, telling apart hex that isn't valid from
+ * bytes that aren't a transaction
+ */
+pub fn decode_raw_tx(hex: &str) -> std::result::Result<Transaction, DecodeTxError> {
+    let bytes = Vec::<u8>::from_hex(hex.trim()).map_err(DecodeTxError::Hex)?;
+    encode::deserialize(&bytes).map_err(DecodeTxError::Consensus)
+}
+
+/**
+ * The script type most of a transaction's inputs spend from, ties going to the earlier input
+ */
+fn dominant_input_type(
+    tx: &Transaction,
+    prevouts: &mut impl PrevoutSource,
+) -> Result<Option<ScriptType>> {
+    let mut counts: Vec<(ScriptType, usize)> = Vec::new();
+    for input in &tx.input {
+        let prevout = prevouts.prevout(&input.previous_output)?;
+        let script_type = ScriptType::of(&prevout.txout.script_pubkey);
+        match counts.iter_mut().find(|(t, _)| *t == script_type) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((script_type, 1)),
+        }
+    }
+
+    let mut dominant: Option<(ScriptType, usize)> = None;
+    for (script_type, count) in counts {
+        if dominant.is_none_or(|(_, most)| count > most) {
+            dominant = Some((script_type, count));
+        }
+    }
+    Ok(dominant.map(|(script_type, _)| script_type))
+}
+
+/**
+ * Witness weight of the block's transactions grouped by their dominant input type,
+ * heaviest first. Each witness byte is one weight unit
+ */
+fn witness_by_type(
+    block: &block::Block,
+    prevouts: &mut impl PrevoutSource,
+) -> Result<Vec<(ScriptType, u64)>> {
+    let mut weights: HashMap<ScriptType, u64> = HashMap::new();
+    for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+        let witness_weight = witness_size(tx) as u64;
+        if witness_weight == 0 {
+            continue;
+        }
+        if let Some(script_type) = dominant_input_type(tx, prevouts)? {
+            *weights.entry(script_type).or_default() += witness_weight;
+        }
+    }
+
+    let mut weights: Vec<(ScriptType, u64)> = weights.into_iter().collect();
+    weights.sort_by_key(|(_, weight)| Reverse(*weight));
+    Ok(weights)
+}
+
+pub fn block_witness_by_type(block_height: u64) -> Result<Vec<(ScriptType, u64)>> {
+    let block = get_block_by_height(block_height)?;
+    witness_by_type(&block, &mut RpcPrevouts::default())
+}
+
+/**
+ * Header timestamps of every block from `start` to `end`
+ */
+fn block_times(start: u64, end: u64) -> Result<Vec<u32>> {
+    let rpc = &*RPC_CLIENT;
+    (start..=end)
+        .map(|height| Ok(rpc.get_block_header(&rpc.get_block_hash(height)?)?.time))
+        .collect()
+}
+
+/**
+ * Seconds between consecutive timestamps. Miners may set a timestamp earlier than its parent's,
+ * so negative intervals are clamped to zero
+ */
+fn block_intervals(times: &[u32]) -> Vec<i64> {
+    times
+        .windows(2)
+        .map(|pair| (pair[1] as i64 - pair[0] as i64).max(0))
+        .collect()
+}
+
+/**
+ * Fraction of `others` that are strictly smaller than `value`
+ */
+fn percentile_rank(value: i64, others: &[i64]) -> Option<f64> {
+    if others.is_empty() {
+        return None;
+    }
+    let below = others.iter().filter(|other| **other < value).count();
+    Some(below as f64 / others.len() as f64)
+}
+
+/**
+ * First and last height of the `window` blocks around `block_height`, kept between height 1
+ * and the tip since the genesis block has no interval
+ */
+fn rank_window(block_height: u64, window: u64, tip: u64) -> (u64, u64) {
+    let first = block_height.saturating_sub(window / 2).max(1);
+    let last = tip.min(block_height.saturating_add(window / 2));
+    (first, last)
+}
+
+/**
+ * Interval of `block_height` and its rank among the other intervals of the window starting at
+ * `first`. `times` holds the timestamps from height `first - 1` to the end of the window
+ */
+fn rank_interval(times: &[u32], first: u64, block_height: u64) -> (i64, Option<f64>) {
+    let mut intervals = block_intervals(times);
+    let own = intervals.remove((block_height - first) as usize);
+    (own, percentile_rank(own, &intervals))
+}
+
+/**
+ * Compares a block's interval against the intervals of the `window` blocks around it
+ */
+pub fn interval_rank(block_height: u64, window: u64) -> Result<(i64, Option<f64>)> {
+    if block_height == 0 {
+        return Err(BitcoinRpcError::ReturnedError(
+            "the genesis block has no interval".to_string(),
+        ));
+    }
+    let rpc = &*RPC_CLIENT;
+    // errors out for heights past the tip
+    rpc.get_block_hash(block_height)?;
+    let (first, last) = rank_window(block_height, window, rpc.get_block_count()?);
+    let times = block_times(first - 1, last)?;
+    Ok(rank_interval(&times, first, block_height))
+}
+
+/**
+ * OP_RETURN outputs and every other output of a block
+ */
+fn data_and_spendable_outputs(block: &block::Block) -> (u64, u64) {
+    let (mut data, mut spendable) = (0, 0);
+    for output in block.txdata.iter().flat_map(|tx| &tx.output) {
+        if output.script_pubkey.is_op_return() {
+            data += 1;
+        } else {
+            spendable += 1;
+        }
+    }
+    (data, spendable)
+}
+
+fn ratio(numerator: u64, denominator: u64) -> Option<f64> {
+    if denominator == 0 {
+        return None;
+    }
+    Some(numerator as f64 / denominator as f64)
+}
+
+/**
+ * Data and spendable output counts of each block in the range
+ */
+fn data_vs_value<I>(blocks: I) -> Result<Vec<(u64, u64, u64)>>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    blocks
+        .into_iter()
+        .map(|block| {
+            let (height, block) = block?;
+            let (data, spendable) = data_and_spendable_outputs(&block);
+            Ok((height, data, spendable))
+        })
+        .collect()
+}
+
+pub fn range_data_vs_value(start: u64, end: u64) -> Result<Vec<(u64, u64, u64)>> {
+    data_vs_value(blocks_in_range(start, end))
+}
+
+/**
+ * Total value paid out by the block's coinbase, in sats
+ */
+fn coinbase_value(block: &block::Block) -> u64 {
+    block.txdata.first().map(output_value).unwrap_or_default()
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CoinbaseValue {
+    pub height: u64,
+    pub total: u64,
+    pub subsidy: u64,
+}
+
+impl CoinbaseValue {
+    /**
+     * Whatever the coinbase claims beyond the subsidy. Miners may claim less than they are
+     * allowed to, so this never goes below zero
+     */
+    pub fn fees(&self) -> u64 {
+        self.total.saturating_sub(self.subsidy)
+    }
+}
+
+fn coinbase_values<I>(blocks: I) -> Result<Vec<CoinbaseValue>>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    blocks
+        .into_iter()
+        .map(|block| {
+            let (height, block) = block?;
+            Ok(CoinbaseValue {
+                height,
+                total: coinbase_value(&block),
+                subsidy: block_subsidy(height),
+            })
+        })
+        .collect()
+}
+
+pub fn range_coinbase_values(start: u64, end: u64) -> Result<Vec<CoinbaseValue>> {
+    coinbase_values(blocks_in_range(start, end))
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Burns {
+    pub data_outputs: u64,
+    pub value_outputs: u64,
+    pub value: u64,
+}
+
+/**
+ * Zero-value OP_RETURN outputs only carry data, any other OP_RETURN or burn address output
+ * destroys the coins sent to it. A zero-value burn address output does neither and is skipped
+ */
+fn burns(block: &block::Block) -> Burns {
+    let mut burns = Burns::default();
+    for output in block.txdata.iter().flat_map(|tx| &tx.output) {
+        let is_op_return = output.script_pubkey.is_op_return();
+        if !is_op_return && !BURN_SCRIPTS.contains(&output.script_pubkey) {
+            continue;
+        }
+        if output.value.to_sat() == 0 {
+            if is_op_return {
+                burns.data_outputs += 1;
+            }
+        } else {
+            burns.value_outputs += 1;
+            burns.value += output.value.to_sat();
+        }
+    }
+    burns
+}
+
+pub fn block_burns(block_height: u64) -> Result<Burns> {
+    Ok(burns(&get_block_by_height(block_height)?))
+}
+
+/**
+ * Gini coefficient of a set of values, 0 when everyone holds the same and approaching 1 when
+ * one holds everything. `None` when there is nothing to distribute
+ */
+fn gini(values: &[u64]) -> Option<f64> {
+    let total: u64 = values.iter().sum();
+    if total == 0 {
+        return None;
+    }
+    let mut values = values.to_vec();
+    values.sort_unstable();
+
+    let n = values.len() as f64;
+    let weighted: f64 = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| (i + 1) as f64 * *value as f64)
+        .sum();
+    Some(2.0 * weighted / (n * total as f64) - (n + 1.0) / n)
+}
+
+pub fn fee_gini(block_height: u64) -> Result<Option<f64>> {
+    let block = get_block_by_height(block_height)?;
+    let fees = block_fees(&block, &mut RpcPrevouts::default())?;
+    let fees: Vec<u64> = fees.iter().map(|fee| fee.fee).collect();
+    Ok(gini(&fees))
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn median(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut values = values.to_vec();
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        Some((values[middle - 1] + values[middle]) / 2.0)
+    } else {
+        Some(values[middle])
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UtxoAge {
+    pub mean_blocks: f64,
+    pub median_blocks: f64,
+    pub mean_days: f64,
+    pub median_days: f64,
+}
+
+/**
+ * How old the outputs spent by the block were, `None` for a coinbase-only block
+ */
+fn utxo_age(
+    block: &block::Block,
+    block_height: u64,
+    prevouts: &mut impl PrevoutSource,
+) -> Result<Option<UtxoAge>> {
+    let (mut blocks, mut days) = (Vec::new(), Vec::new());
+    for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+        for input in &tx.input {
+            let prevout = prevouts.prevout(&input.previous_output)?;
+            blocks.push(block_height.saturating_sub(prevout.height) as f64);
+            let age_secs = (block.header.time as i64 - prevout.time as i64).max(0);
+            days.push(age_secs as f64 / 86_400.0);
+        }
+    }
+
+    Ok(mean(&blocks).map(|mean_blocks| UtxoAge {
+        mean_blocks,
+        median_blocks: median(&blocks).unwrap_or_default(),
+        mean_days: mean(&days).unwrap_or_default(),
+        median_days: median(&days).unwrap_or_default(),
+    }))
+}
+
+pub fn avg_utxo_age(block_height: u64) -> Result<Option<UtxoAge>> {
+    let block = get_block_by_height(block_height)?;
+    utxo_age(&block, block_height, &mut RpcPrevouts::default())
+}
+
+/**
+ * Transaction count and total fees in sats of a block, read from `getblockstats` instead of
+ * looking up every prevout
+ */
+fn block_tx_count_and_fees(block_height: u64) -> Result<(u64, u64)> {
+    let rpc = &*RPC_CLIENT;
+    let stats = rpc.get_block_stats_fields(
+        block_height,
+        &[BlockStatsFields::Txs, BlockStatsFields::TotalFee],
+    )?;
+    Ok((
+        stats.txs.unwrap_or_default() as u64,
+        stats.total_fee.unwrap_or_default().to_sat(),
+    ))
+}
+
+fn txs_per_fee_btc(stats: &[(u64, u64)]) -> Option<f64> {
+    let txs: u64 = stats.iter().map(|(txs, _)| txs).sum();
+    let fees: u64 = stats.iter().map(|(_, fees)| fees).sum();
+    if fees == 0 {
+        return None;
+    }
+    Some(txs as f64 / (fees as f64 / 100_000_000.0))
+}
+
+/**
+ * Transactions per BTC of fees over the whole range, and the percent change from the first
+ * half of the range to the second
+ */
+fn tx_per_fee_btc_trend(stats: &[(u64, u64)]) -> (Option<f64>, Option<f64>) {
+    let (first, second) = stats.split_at(stats.len() / 2);
+    let trend = match (txs_per_fee_btc(first), txs_per_fee_btc(second)) {
+        (Some(first), Some(second)) => percent_change(first, second),
+        _ => None,
+    };
+    (txs_per_fee_btc(stats), trend)
+}
+
+pub fn range_tx_per_fee_btc(start: u64, end: u64) -> Result<(Option<f64>, Option<f64>)> {
+    let stats = (start..=end)
+        .map(block_tx_count_and_fees)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(tx_per_fee_btc_trend(&stats))
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DecodedBits {
+    pub target: Target,
+    pub difficulty: f64,
+    pub expected_hashes: f64,
+}
+
+/**
+ * Expands the compact `nBits` encoding, a 3 byte mantissa shifted left by
+ * `8 * (exponent - 3)` bits, into the full target. Each hash has a `target / 2^256` chance
+ * of being below it, which works out to `difficulty * 2^32` hashes per block.
+ * Like Core's `SetCompact`, rejects a negative mantissa and one shifted past 256 bits, and a
+ * zero target no hash could ever meet
+ */
+pub fn decode_bits(bits: u32) -> std::result::Result<DecodedBits, String> {
+    let exponent = bits >> 24;
+    let mantissa = bits & 0x007f_ffff;
+    if mantissa != 0 && bits & 0x0080_0000 != 0 {
+        return Err(format!("{:#010x} has the sign bit set", bits));
+    }
+    let overflows =
+        exponent > 34 || (mantissa > 0xff && exponent > 33) || (mantissa > 0xffff && exponent > 32);
+    if mantissa != 0 && overflows {
+        return Err(format!("{:#010x} overflows 256 bits", bits));
+    }
+    let target = Target::from_compact(CompactTarget::from_consensus(bits));
+    if target == Target::ZERO {
+        return Err(format!("{:#010x} encodes a zero target", bits));
+    }
+    let difficulty = target.difficulty_float();
+    Ok(DecodedBits {
+        target,
+        difficulty,
+        expected_hashes: difficulty * 2f64.powi(32),
+    })
+}
+
+/**
+ * Accepts `nBits` in decimal or as hex prefixed with `0x`
+ */
+fn parse_bits(bits: &str) -> std::result::Result<u32, String> {
+    match bits.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => bits.parse(),
+    }
+    .map_err(|e| e.to_string())
+}
+
+/**
+ * Estimates the share of a block's inputs that spend change the same owner created within the
+ * last `window` blocks. The common-input-ownership heuristic assumes every input of a
+ * transaction belongs to one owner, so the change heuristic can pick out which of the parent's
+ * outputs went back to that owner. An input spending that output, whether the parent is in this
+ * block or an earlier one, is the owner spending their own coins again. Coinjoins and wallets
+ * that vary their change type break both assumptions, so treat the result as a rough estimate
+ */
+fn self_spends<S>(
+    block: &block::Block,
+    block_height: u64,
+    window: u64,
+    source: &mut S,
+) -> Result<Option<f64>>
+where
+    S: PrevoutSource + TransactionSource,
+{
+    let (mut inputs, mut self_spends) = (0, 0);
+    for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+        for input in &tx.input {
+            inputs += 1;
+            let prevout = source.prevout(&input.previous_output)?;
+            if block_height.saturating_sub(prevout.height) > window {
+                continue;
+            }
+            let parent = source.transaction(&input.previous_output.txid)?;
+            if change_output(&parent, source)? == Some(input.previous_output.vout as usize) {
+                self_spends += 1;
+            }
+        }
+    }
+    Ok(ratio(self_spends, inputs))
+}
+
+pub fn block_self_spends(block_height: u64, window: u64) -> Result<Option<f64>> {
+    let block = get_block_by_height(block_height)?;
+    self_spends(&block, block_height, window, &mut RpcPrevouts::default())
+}
+
+const BLOCK_VSIZE: u64 = 1_000_000;
+
+/**
+ * How many full blocks it would take to mine `vsize` vbytes
+ */
+fn blocks_of_backlog(vsize: u64) -> f64 {
+    vsize as f64 / BLOCK_VSIZE as f64
+}
+
+/**
+ * Fee rate of the cheapest transaction that still fits in the next `blocks` blocks when the
+ * mempool is mined highest fee rate first. `None` when the whole mempool fits
+ */
+fn clearing_fee_rate(entries: &[(u64, u64)], blocks: u64) -> Option<f64> {
+    let mut rates: Vec<(f64, u64)> = entries
+        .iter()
+        .map(|(vsize, fee)| (*fee as f64 / *vsize as f64, *vsize))
+        .collect();
+    rates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let space = blocks * BLOCK_VSIZE;
+    let mut used = 0;
+    let mut last_included = None;
+    for (rate, vsize) in rates {
+        used += vsize;
+        if used > space {
+            return last_included;
+        }
+        last_included = Some(rate);
+    }
+    None
+}
+
+/**
+ * Backlog in blocks and the fee rate needed to be mined within `blocks` blocks
+ */
+pub fn mempool_backlog(blocks: u64) -> Result<(f64, Option<f64>)> {
+    let rpc = &*RPC_CLIENT;
+    let entries: Vec<(u64, u64)> = rpc
+        .get_raw_mempool_verbose()?
+        .values()
+        .map(|entry| (entry.vsize, entry.fees.base.to_sat()))
+        .collect();
+    let vsize = entries.iter().map(|(vsize, _)| vsize).sum();
+    Ok((
+        blocks_of_backlog(vsize),
+        clearing_fee_rate(&entries, blocks),
+    ))
+}
+
+/**
+ * Share of non-coinbase transactions with exactly one input
+ */
+fn simple_spends(block: &block::Block) -> Option<f64> {
+    let txs: Vec<&Transaction> = block.txdata.iter().filter(|tx| !tx.is_coinbase()).collect();
+    let simple = txs.iter().filter(|tx| tx.input.len() == 1).count();
+    ratio(simple as u64, txs.len() as u64)
+}
+
+pub fn block_simple_spends(block_height: u64) -> Result<Option<f64>> {
+    Ok(simple_spends(&get_block_by_height(block_height)?))
+}
+
+const MAX_BLOCK_WEIGHT: u64 = 4_000_000;
+
+/**
+ * Median weight of the block's non-coinbase transactions
+ */
+fn median_tx_weight(block: &block::Block) -> Option<f64> {
+    let weights: Vec<f64> = block
+        .txdata
+        .iter()
+        .filter(|tx| !tx.is_coinbase())
+        .map(|tx| tx.weight().to_wu() as f64)
+        .collect();
+    median(&weights)
+}
+
+/**
+ * How full the block is, and how many more median sized transactions would have fit in the
+ * weight left over. The count is `None` when there is no transaction to take the median of
+ */
+fn throughput_ceiling(block: &block::Block) -> (f64, Option<u64>) {
+    let weight = block.weight().to_wu();
+    let remaining = MAX_BLOCK_WEIGHT.saturating_sub(weight);
+    let extra = median_tx_weight(block).map(|median| (remaining as f64 / median) as u64);
+    (weight as f64 / MAX_BLOCK_WEIGHT as f64, extra)
+}
+
+pub fn block_throughput_ceiling(block_height: u64) -> Result<(f64, Option<u64>)> {
+    Ok(throughput_ceiling(&get_block_by_height(block_height)?))
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WeeklyAddresses {
+    pub year: i32,
+    pub week: u32,
+    pub addresses: u64,
+    pub growth: Option<f64>,
+}
+
+fn iso_week(time: u32) -> (i32, u32) {
+    let week = DateTime::from_timestamp(time as i64, 0)
+        .unwrap_or_default()
+        .iso_week();
+    (week.year(), week.week())
+}
+
+/**
+ * Distinct addresses paid in each ISO week. Only the current week's addresses are kept in
+ * memory, and a block timestamped before the current week is counted in the current week
+ */
+fn address_growth<I>(blocks: I, network: Network) -> Result<Vec<WeeklyAddresses>>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    let mut weeks: Vec<WeeklyAddresses> = Vec::new();
+    let mut current: Option<(i32, u32)> = None;
+    let mut addresses: HashSet<Address> = HashSet::new();
+
+    let mut close_week = |week: (i32, u32), addresses: &mut HashSet<Address>| {
+        let count = addresses.len() as u64;
+        let growth = weeks
+            .last()
+            .and_then(|last| percent_change(last.addresses as f64, count as f64));
+        weeks.push(WeeklyAddresses {
+            year: week.0,
+            week: week.1,
+            addresses: count,
+            growth,
+        });
+        addresses.clear();
+    };
+
+    for block in blocks {
+        let (_, block) = block?;
+        let week = iso_week(block.header.time);
+        match current {
+            Some(current_week) if week > current_week => {
+                close_week(current_week, &mut addresses);
+                current = Some(week);
+            }
+            None => current = Some(week),
+            _ => {}
+        }
+        for output in block.txdata.iter().flat_map(|tx| &tx.output) {
+            if let Ok(address) = Address::from_script(&output.script_pubkey, network) {
+                addresses.insert(address);
+            }
+        }
+    }
+    if let Some(current_week) = current {
+        close_week(current_week, &mut addresses);
+    }
+    Ok(weeks)
+}
+
+pub fn range_address_growth(start: u64, end: u64) -> Result<Vec<WeeklyAddresses>> {
+    address_growth(blocks_in_range(start, end), get_chain()?)
+}
+
+/**
+ * Transactions that paid more in fees than they sent, as txid, fee and output value
+ */
+fn overpaid_txs(
+    block: &block::Block,
+    prevouts: &mut impl PrevoutSource,
+) -> Result<Vec<(Txid, u64, u64)>> {
+    let mut overpaid = Vec::new();
+    for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+        let fee = tx_fee(tx, prevouts)?;
+        let value = output_value(tx);
+        if fee.fee > value {
+            overpaid.push((fee.txid, fee.fee, value));
+        }
+    }
+    Ok(overpaid)
+}
+
+pub fn block_overpaid_txs(block_height: u64) -> Result<Vec<(Txid, u64, u64)>> {
+    let block = get_block_by_height(block_height)?;
+    overpaid_txs(&block, &mut RpcPrevouts::default())
+}
+
+const COINBASE_MATURITY: u64 = 100;
+
+/**
+ * Average number of blocks past maturity that coinbase outputs waited before being spent.
+ * `coinbases` maps coinbase txids mined before the range to their heights, coinbases mined
+ * inside the range are picked up as the blocks stream past
+ */
+fn coinbase_spend_maturity<I>(mut coinbases: HashMap<Txid, u64>, blocks: I) -> Result<Option<f64>>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    let mut waits = Vec::new();
+    for block in blocks {
+        let (height, block) = block?;
+        for tx in &block.txdata {
+            if tx.is_coinbase() {
+                coinbases.insert(tx.compute_txid(), height);
+                continue;
+            }
+            for input in &tx.input {
+                if let Some(mined) = coinbases.get(&input.previous_output.txid) {
+                    let depth = height - mined;
+                    waits.push(depth.saturating_sub(COINBASE_MATURITY) as f64);
+                }
+            }
+        }
+    }
+    Ok(mean(&waits))
+}
+
+/**
+ * Only coinbases from the `lookback` blocks before `start` onwards are recognised, which keeps
+ * the backward scan to one cheap `getblock` call per block
+ */
+pub fn range_coinbase_spend_maturity(start: u64, end: u64, lookback: u64) -> Result<Option<f64>> {
+    let rpc = &*RPC_CLIENT;
+    let mut coinbases = HashMap::new();
+    for height in start.saturating_sub(lookback)..start {
+        let info = rpc.get_block_info(&rpc.get_block_hash(height)?)?;
+        if let Some(coinbase) = info.tx.first() {
+            coinbases.insert(*coinbase, height);
+        }
+    }
+    coinbase_spend_maturity(coinbases, blocks_in_range(start, end))
+}
+
+/**
+ * A taproot key path spend carries only a signature, plus an annex when the last element
+ * starts with 0x50
+ */
+fn is_taproot_key_path(input: &TxIn) -> bool {
+    match input.witness.len() {
+        1 => true,
+        2 => input
+            .witness
+            .last()
+            .is_some_and(|annex| annex.first() == Some(&0x50)),
+        _ => false,
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct UnlockingSize {
+    pub inputs: u64,
+    pub bytes: u64,
+    pub weight: u64,
+}
+
+impl UnlockingSize {
+    pub fn avg_bytes(&self) -> Option<f64> {
+        ratio(self.bytes, self.inputs)
+    }
+
+    pub fn avg_vbytes(&self) -> Option<f64> {
+        ratio(self.weight, self.inputs * 4)
+    }
+}
+
+/**
+ * Size of the data unlocking taproot key path, P2WPKH and P2PKH inputs. Script sig bytes weigh
+ * four units each while witness bytes weigh one
+ */
+fn taproot_efficiency(
+    block: &block::Block,
+    prevouts: &mut impl PrevoutSource,
+) -> Result<HashMap<ScriptType, UnlockingSize>> {
+    let mut sizes: HashMap<ScriptType, UnlockingSize> = HashMap::new();
+    for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+        for input in &tx.input {
+            let prevout = prevouts.prevout(&input.previous_output)?;
+            let script_type = match ScriptType::of(&prevout.txout.script_pubkey) {
+                ScriptType::P2tr if is_taproot_key_path(input) => ScriptType::P2tr,
+                script_type @ (ScriptType::P2wpkh | ScriptType::P2pkh) => script_type,
+                _ => continue,
+            };
+            let script_sig = input.script_sig.len() as u64;
+            // an empty witness still serializes its item count, but only in segwit transactions
+            let witness = if input.witness.is_empty() {
+                0
+            } else {
+                input.witness.size() as u64
+            };
+            let size = sizes.entry(script_type).or_default();
+            size.inputs += 1;
+            size.bytes += script_sig + witness;
+            size.weight += script_sig * 4 + witness;
+        }
+    }
+    Ok(sizes)
+}
+
+pub fn block_taproot_efficiency(block_height: u64) -> Result<HashMap<ScriptType, UnlockingSize>> {
+    let block = get_block_by_height(block_height)?;
+    taproot_efficiency(&block, &mut RpcPrevouts::default())
+}
+
+/**
+ * Number of distinct output values in the block and the `top` most repeated values with their
+ * counts. OP_RETURN outputs carry data, not payments, so they are left out
+ */
+fn denominations(block: &block::Block, top: usize) -> (usize, Vec<(u64, u64)>) {
+    let mut counts: HashMap<u64, u64> = HashMap::new();
+    for output in block.txdata.iter().flat_map(|tx| &tx.output) {
+        if !output.script_pubkey.is_op_return() {
+            *counts.entry(output.value.to_sat()).or_default() += 1;
+        }
+    }
+
+    let distinct = counts.len();
+    let mut counts: Vec<(u64, u64)> = counts.into_iter().collect();
+    counts.sort_by_key(|(value, count)| (Reverse(*count), *value));
+    counts.truncate(top);
+    (distinct, counts)
+}
+
+pub fn block_denominations(block_height: u64, top: usize) -> Result<(usize, Vec<(u64, u64)>)> {
+    Ok(denominations(&get_block_by_height(block_height)?, top))
+}
+
+const TX_COUNT_CHECKPOINT_INTERVAL: u64 = 1_000;
+
+/**
+ * Cumulative transaction counts at checkpoint heights, so a later run only has to sum the
+ * blocks past the last checkpoint. Saved as one `height cumulative` pair per line
+ */
+#[derive(Debug, Default, PartialEq)]
+struct TxCountCache {
+    checkpoints: BTreeMap<u64, u64>,
+}
+
+impl TxCountCache {
+    fn load(path: &Path) -> Result<Self> {
+        let mut cache = TxCountCache::default();
+        if !path.exists() {
+            return Ok(cache);
+        }
+        for line in fs::read_to_string(path)?.lines() {
+            let mut parts = line.split_whitespace().map(str::parse::<u64>);
+            match (parts.next(), parts.next()) {
+                (Some(Ok(height)), Some(Ok(total))) => {
+                    cache.checkpoints.insert(height, total);
+                }
+                _ => return Err(BitcoinRpcError::UnexpectedStructure),
+            }
+        }
+        Ok(cache)
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let lines: String = self
+            .checkpoints
+            .iter()
+            .map(|(height, total)| format!("{} {}\n", height, total))
+            .collect();
+        fs::write(path, lines)?;
+        Ok(())
+    }
+
+    /**
+     * Picks up from the closest checkpoint at or below `height`
+     */
+    fn cumulative<F>(&mut self, height: u64, mut tx_count: F) -> Result<u64>
+    where
+        F: FnMut(u64) -> Result<u64>,
+    {
+        let (mut next, mut total) = match self.checkpoints.range(..=height).next_back() {
+            Some((checkpoint, total)) => (checkpoint + 1, *total),
+            None => (0, 0),
+        };
+        while next <= height {
+            total += tx_count(next)?;
+            if (next + 1) % TX_COUNT_CHECKPOINT_INTERVAL == 0 {
+                self.checkpoints.insert(next, total);
+            }
+            next += 1;
+        }
+        self.checkpoints.insert(height, total);
+        Ok(total)
+    }
+
+    /**
+     * First height at which the cumulative count reaches `milestone`, or `None` if the chain
+     * hasn't got there by `tip`
+     */
+    fn milestone<F>(&mut self, milestone: u64, tip: u64, mut tx_count: F) -> Result<Option<u64>>
+    where
+        F: FnMut(u64) -> Result<u64>,
+    {
+        let (mut next, mut total) = match self
+            .checkpoints
+            .iter()
+            .take_while(|(_, total)| **total < milestone)
+            .last()
+        {
+            Some((checkpoint, total)) => (checkpoint + 1, *total),
+            None => (0, 0),
+        };
+        while next <= tip {
+            total += tx_count(next)?;
+            if total >= milestone {
+                self.checkpoints.insert(next, total);
+                return Ok(Some(next));
+            }
+            if (next + 1) % TX_COUNT_CHECKPOINT_INTERVAL == 0 {
+                self.checkpoints.insert(next, total);
+            }
+            next += 1;
+        }
+        Ok(None)
+    }
+}
+
+fn block_tx_count(block_height: u64) -> Result<u64> {
+    let rpc = &*RPC_CLIENT;
+    let stats = rpc.get_block_stats_fields(block_height, &[BlockStatsFields::Txs])?;
+    Ok(stats.txs.unwrap_or_default() as u64)
+}
+
+/**
+ * Cumulative transaction count up to `block_height` and, given a `milestone`, the height of
+ * the block that crossed it. Checkpoints are read from and written back to `state` when given
+ */
+pub fn tx_count_milestone(
+    block_height: u64,
+    milestone: Option<u64>,
+    state: Option<&Path>,
+) -> Result<(u64, Option<u64>)> {
+    let mut cache = match state {
+        Some(path) => TxCountCache::load(path)?,
+        None => TxCountCache::default(),
+    };
+    let total = cache.cumulative(block_height, block_tx_count)?;
+    let crossed = match milestone {
+        Some(milestone) => {
+            let tip = RPC_CLIENT.get_block_count()?;
+            cache.milestone(milestone, tip, block_tx_count)?
+        }
+        None => None,
+    };
+    if let Some(path) = state {
+        cache.save(path)?;
+    }
+    Ok((total, crossed))
+}
+
+/**
+ * Median output value of a block in sats, leaving out zero-value OP_RETURN outputs
+ */
+fn median_output_value(block: &block::Block) -> Option<f64> {
+    let values: Vec<f64> = block
+        .txdata
+        .iter()
+        .flat_map(|tx| &tx.output)
+        .filter(|output| !(output.script_pubkey.is_op_return() && output.value.to_sat() == 0))
+        .map(|output| output.value.to_sat() as f64)
+        .collect();
+    median(&values)
+}
+
+fn median_output_values<I>(blocks: I) -> Result<Vec<(u64, Option<f64>)>>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    blocks
+        .into_iter()
+        .map(|block| {
+            let (height, block) = block?;
+            Ok((height, median_output_value(&block)))
+        })
+        .collect()
+}
+
+pub fn range_median_output_values(start: u64, end: u64) -> Result<Vec<(u64, Option<f64>)>> {
+    median_output_values(blocks_in_range(start, end))
+}
+
+/**
+ * Heights and intervals in seconds of the blocks that took longer than `threshold_mins`.
+ * `times` starts with the timestamp of the block before `start`
+ */
+fn slow_blocks(start: u64, times: &[u32], threshold_mins: i64) -> Vec<(u64, i64)> {
+    block_intervals(times)
+        .into_iter()
+        .zip(start..)
+        .filter(|(interval, _)| *interval > threshold_mins * 60)
+        .map(|(interval, height)| (height, interval))
+        .collect()
+}
+
+pub fn slow_block_alerts(start: u64, end: u64, threshold_mins: i64) -> Result<Vec<(u64, i64)>> {
+    let start = start.max(1);
+    Ok(slow_blocks(
+        start,
+        &block_times(start - 1, end)?,
+        threshold_mins,
+    ))
+}
+
+/**
+ * Share of all the value output in a block that the coinbase pays out
+ */
+fn coinbase_value_share(block: &block::Block) -> Option<f64> {
+    let total: u64 = block.txdata.iter().map(output_value).sum();
+    ratio(coinbase_value(block), total)
+}
+
+pub fn block_coinbase_value_share(block_height: u64) -> Result<Option<f64>> {
+    Ok(coinbase_value_share(&get_block_by_height(block_height)?))
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct SequenceUsage {
+    pub relative_lock: u64,
+    pub rbf: u64,
+    pub other: u64,
+}
+
+/**
+ * Counts each non-coinbase transaction that has an input with a non-final sequence once.
+ * Relative lock times are only enforced from version 2 and also signal RBF, so they are
+ * counted first, then RBF signals, then anything else like the 0xfffffffe that only enables
+ * the absolute lock time
+ */
+fn sequence_usage(block: &block::Block) -> SequenceUsage {
+    let mut usage = SequenceUsage::default();
+    for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+        let sequences = || tx.input.iter().map(|input| input.sequence);
+        if tx.version >= transaction::Version::TWO
+            && sequences().any(|sequence| sequence.is_relative_lock_time())
+        {
+            usage.relative_lock += 1;
+        } else if sequences().any(|sequence| sequence.is_rbf()) {
+            usage.rbf += 1;
+        } else if sequences().any(|sequence| sequence != Sequence::MAX) {
+            usage.other += 1;
+        }
+    }
+    usage
+}
+
+pub fn block_sequence_usage(block_height: u64) -> Result<SequenceUsage> {
+    Ok(sequence_usage(&get_block_by_height(block_height)?))
+}
+
+/**
+ * Reads `date,price` lines with dates like 2024-04-20, skipping a header line if there is one
+ */
+fn load_prices(path: &Path) -> std::result::Result<BTreeMap<NaiveDate, f64>, Box<dyn Error>> {
+    let mut prices = BTreeMap::new();
+    for (i, line) in fs::read_to_string(path)?.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (date, price) = line
+            .split_once(',')
+            .ok_or_else(|| format!("line {}: expected date,price", i + 1))?;
+        let date = match NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(_) if i == 0 => continue,
+            Err(e) => return Err(format!("line {}: {}", i + 1, e).into()),
+        };
+        let price: f64 = price
+            .trim()
+            .parse()
+            .map_err(|e| format!("line {}: {}", i + 1, e))?;
+        prices.insert(date, price);
+    }
+    Ok(prices)
+}
+
+/**
+ * Price on the date closest to `time`, or `None` when the date falls outside the prices given
+ */
+fn price_at(prices: &BTreeMap<NaiveDate, f64>, time: u32) -> Option<f64> {
+    let date = DateTime::from_timestamp(time as i64, 0)?.date_naive();
+    let before = prices.range(..=date).next_back();
+    let after = prices.range(date..).next();
+    match (before, after) {
+        (Some((before, before_price)), Some((after, after_price))) => {
+            if date - *before <= *after - date {
+                Some(*before_price)
+            } else {
+                Some(*after_price)
+            }
+        }
+        _ => None,
+    }
+}
+
+fn fees_usd(fees: u64, time: u32, prices: &BTreeMap<NaiveDate, f64>) -> Option<f64> {
+    price_at(prices, time).map(|price| fees as f64 / 100_000_000.0 * price)
+}
+
+/**
+ * Total fees of a block in sats and, if the price file covers its date, in USD
+ */
+pub fn block_fee_usd(
+    block_height: u64,
+    price_csv: &Path,
+) -> std::result::Result<(u64, Option<f64>), Box<dyn Error>> {
+    let prices = load_prices(price_csv)?;
+    let (_, fees) = block_tx_count_and_fees(block_height)?;
+    let time = get_block_time(block_height)?.num_seconds() as u32;
+    Ok((fees, fees_usd(fees, time, &prices)))
+}
+
+/**
+ * `OP_FALSE OP_IF OP_PUSHBYTES_3 "ord"`, the start of an ordinals inscription envelope
+ */
+const INSCRIPTION_ENVELOPE: [u8; 6] = [0x00, 0x63, 0x03, b'o', b'r', b'd'];
+
+/**
+ * Whether any input reveals an inscription. The envelope lives in the tapscript of a script
+ * path spend, so every witness element is searched for it
+ */
+fn has_inscription(tx: &Transaction) -> bool {
+    tx.input
+        .iter()
+        .flat_map(|input| input.witness.iter())
+        .any(|element| {
+            element
+                .windows(INSCRIPTION_ENVELOPE.len())
+                .any(|window| window == INSCRIPTION_ENVELOPE)
+        })
+}
+
+/**
+ * Blocks with at least one inscription and the total number of blocks
+ */
+fn inscription_prevalence<I>(blocks: I) -> Result<(u64, u64)>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    let (mut inscribed, mut total) = (0, 0);
+    for block in blocks {
+        let (_, block) = block?;
+        total += 1;
+        if block.txdata.iter().any(has_inscription) {
+            inscribed += 1;
+        }
+    }
+    Ok((inscribed, total))
+}
+
+pub fn range_inscription_prevalence(start: u64, end: u64) -> Result<(u64, u64)> {
+    inscription_prevalence(blocks_in_range(start, end))
+}
+
+/**
+ * Population standard deviation
+ */
+fn std_dev(values: &[f64]) -> Option<f64> {
+    let mean = mean(values)?;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / values.len() as f64;
+    Some(variance.sqrt())
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SizeVolatility {
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl SizeVolatility {
+    /**
+     * Standard deviation relative to the mean
+     */
+    pub fn coefficient_of_variation(&self) -> Option<f64> {
+        if self.mean == 0.0 {
+            return None;
+        }
+        Some(self.std_dev / self.mean)
+    }
+}
+
+fn size_volatility<I>(blocks: I) -> Result<Option<SizeVolatility>>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    let mut sizes = Vec::new();
+    for block in blocks {
+        let (_, block) = block?;
+        sizes.push(block.total_size() as f64);
+    }
+    Ok(mean(&sizes).map(|mean| SizeVolatility {
+        mean,
+        std_dev: std_dev(&sizes).unwrap_or_default(),
+    }))
+}
+
+pub fn range_size_volatility(start: u64, end: u64) -> Result<Option<SizeVolatility>> {
+    size_volatility(blocks_in_range(start, end))
+}
+
+fn utc_date(time: u32) -> NaiveDate {
+    DateTime::from_timestamp(time as i64, 0)
+        .unwrap_or_default()
+        .date_naive()
+}
+
+/**
+ * Share of the block's inputs spending outputs mined on the same UTC date as the block
+ */
+fn same_day_spends(block: &block::Block, prevouts: &mut impl PrevoutSource) -> Result<Option<f64>> {
+    let date = utc_date(block.header.time);
+    let (mut inputs, mut same_day) = (0, 0);
+    for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+        for input in &tx.input {
+            inputs += 1;
+            if utc_date(prevouts.prevout(&input.previous_output)?.time) == date {
+                same_day += 1;
+            }
+        }
+    }
+    Ok(ratio(same_day, inputs))
+}
+
+pub fn block_same_day_spends(block_height: u64) -> Result<Option<f64>> {
+    let block = get_block_by_height(block_height)?;
+    same_day_spends(&block, &mut RpcPrevouts::default())
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct CommitmentAudit {
+    pub checked: u64,
+    pub skipped: u64,
+    pub mismatched: Vec<u64>,
+}
+
+/**
+ * Recomputes the witness merkle root of every block carrying witness data and compares it against
+ * the commitment in its coinbase. Blocks without witness data have nothing to commit to, which
+ * covers every block from before segwit
+ */
+fn commitment_audit<I>(blocks: I) -> Result<CommitmentAudit>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    let mut audit = CommitmentAudit::default();
+    for block in blocks {
+        let (height, block) = block?;
+        let has_witness = block
+            .txdata
+            .iter()
+            .flat_map(|tx| &tx.input)
+            .any(|input| !input.witness.is_empty());
+        if !has_witness {
+            audit.skipped += 1;
+            continue;
+        }
+        audit.checked += 1;
+        if !block.check_witness_commitment() {
+            audit.mismatched.push(height);
+        }
+    }
+    Ok(audit)
+}
+
+pub fn range_commitment_audit(start: u64, end: u64) -> Result<CommitmentAudit> {
+    commitment_audit(blocks_in_range(start, end))
+}
+
+/**
+ * Distinct addresses paid in a block, its spendable output count, and the ratio between them.
+ * Spendable outputs with no address form (bare multisig and the like) count only towards the total
+ */
+fn address_reuse_ratio(block: &block::Block, network: Network) -> (u64, u64, Option<f64>) {
+    let mut addresses: HashSet<Address> = HashSet::new();
+    let mut spendable = 0;
+    for output in block.txdata.iter().flat_map(|tx| &tx.output) {
+        if output.script_pubkey.is_op_return() {
+            continue;
+        }
+        spendable += 1;
+        if let Ok(address) = Address::from_script(&output.script_pubkey, network) {
+            addresses.insert(address);
+        }
+    }
+    let distinct = addresses.len() as u64;
+    (distinct, spendable, ratio(distinct, spendable))
+}
+
+pub fn block_address_reuse_ratio(block_height: u64) -> Result<(u64, u64, Option<f64>)> {
+    Ok(address_reuse_ratio(
+        &get_block_by_height(block_height)?,
+        get_chain()?,
+    ))
+}
+
+/**
+ * The lowest fee rate paying transaction that made it into the block, `None` for a block with
+ * only a coinbase
+ */
+fn inclusion_floor(
+    block: &block::Block,
+    prevouts: &mut impl PrevoutSource,
+) -> Result<Option<TxFee>> {
+    Ok(block_fees(block, prevouts)?
+        .into_iter()
+        .min_by(|a, b| a.fee_rate().total_cmp(&b.fee_rate())))
+}
+
+pub fn block_inclusion_floor(block_height: u64) -> Result<Option<TxFee>> {
+    let block = get_block_by_height(block_height)?;
+    inclusion_floor(&block, &mut RpcPrevouts::default())
+}
+
+/**
+ * Labels of the mempool age buckets and the age in seconds each runs up to
+ */
+const MEMPOOL_AGE_BUCKETS: [(&str, u64); 5] = [
+    ("<10min", 600),
+    ("10-60min", 3_600),
+    ("1-6h", 21_600),
+    ("6-24h", 86_400),
+    (">24h", u64::MAX),
+];
+
+/**
+ * Transaction count and vsize in each of `MEMPOOL_AGE_BUCKETS`, from `(entry time, vsize)` pairs.
+ * Entry times ahead of `now` count as just arrived
+ */
+fn mempool_ages(entries: &[(u64, u64)], now: u64) -> [(u64, u64); 5] {
+    let mut buckets = [(0, 0); 5];
+    for (time, vsize) in entries {
+        let age = now.saturating_sub(*time);
+        let index = MEMPOOL_AGE_BUCKETS
+            .iter()
+            .position(|(_, upper)| age < *upper)
+            .unwrap_or(MEMPOOL_AGE_BUCKETS.len() - 1);
+        buckets[index].0 += 1;
+        buckets[index].1 += vsize;
+    }
+    buckets
+}
+
+pub fn current_mempool_ages() -> Result<[(u64, u64); 5]> {
+    let rpc = &*RPC_CLIENT;
+    let entries: Vec<(u64, u64)> = rpc
+        .get_raw_mempool_verbose()?
+        .values()
+        .map(|entry| (entry.time, entry.vsize))
+        .collect();
+    Ok(mempool_ages(&entries, Utc::now().timestamp() as u64))
+}
+
+/**
+ * Taproot outputs created in the range and how many of them were spent again before the range
+ * ended, including spends later in the same block
+ */
+fn taproot_churn<I>(blocks: I) -> Result<(u64, u64)>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    let mut unspent: HashSet<OutPoint> = HashSet::new();
+    let (mut created, mut spent) = (0, 0);
+    for block in blocks {
+        let (_, block) = block?;
+        for tx in &block.txdata {
+            for input in &tx.input {
+                if unspent.remove(&input.previous_output) {
+                    spent += 1;
+                }
+            }
+            let txid = tx.compute_txid();
+            for (vout, output) in tx.output.iter().enumerate() {
+                if output.script_pubkey.is_p2tr() {
+                    created += 1;
+                    unspent.insert(OutPoint::new(txid, vout as u32));
+                }
+            }
+        }
+    }
+    Ok((created, spent))
+}
+
+pub fn range_taproot_churn(start: u64, end: u64) -> Result<(u64, u64)> {
+    taproot_churn(blocks_in_range(start, end))
+}
+
+/**
+ * Population skewness of `values`, positive when the tail runs out to the right. `None` when
+ * every value is the same
+ */
+fn skewness(values: &[f64]) -> Option<f64> {
+    let mean = mean(values)?;
+    let std_dev = std_dev(values)?;
+    if std_dev == 0.0 {
+        return None;
+    }
+    let third_moment = values
+        .iter()
+        .map(|value| (value - mean).powi(3))
+        .sum::<f64>()
+        / values.len() as f64;
+    Some(third_moment / std_dev.powi(3))
+}
+
+/**
+ * Skewness of the interval leading up to each block of the range, the genesis block having none
+ */
+pub fn interval_skew(start: u64, end: u64) -> Result<Option<f64>> {
+    let start = start.max(1);
+    let intervals: Vec<f64> = block_intervals(&block_times(start - 1, end)?)
+        .into_iter()
+        .map(|interval| interval as f64)
+        .collect();
+    Ok(skewness(&intervals))
+}
+
+/**
+ * Whether a transaction is an ordinary payment: no OP_RETURN output, no locktime, and every
+ * input and output of the same standard script type
+ */
+fn is_plain_transfer(tx: &Transaction, prevouts: &mut impl PrevoutSource) -> Result<bool> {
+    if tx.lock_time.to_consensus_u32() != 0 {
+        return Ok(false);
+    }
+    let mut script_types = HashSet::new();
+    for output in &tx.output {
+        script_types.insert(ScriptType::of(&output.script_pubkey));
+    }
+    for input in &tx.input {
+        let prevout = prevouts.prevout(&input.previous_output)?;
+        script_types.insert(ScriptType::of(&prevout.txout.script_pubkey));
+    }
+    Ok(script_types.len() == 1
+        && !script_types.contains(&ScriptType::OpReturn)
+        && !script_types.contains(&ScriptType::Other))
+}
+
+/**
+ * Share of non-coinbase transactions that are plain transfers
+ */
+fn plain_transfers(block: &block::Block, prevouts: &mut impl PrevoutSource) -> Result<Option<f64>> {
+    let (mut plain, mut total) = (0, 0);
+    for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+        total += 1;
+        if is_plain_transfer(tx, prevouts)? {
+            plain += 1;
+        }
+    }
+    Ok(ratio(plain, total))
+}
+
+pub fn block_plain_transfers(block_height: u64) -> Result<Option<f64>> {
+    let block = get_block_by_height(block_height)?;
+    plain_transfers(&block, &mut RpcPrevouts::default())
+}
+
+const YEAR_SECS: i64 = 365 * 86_400;
+
+/**
+ * Count and total vsize of the block's transactions spending at least one output created more
+ * than a year before the block's timestamp
+ */
+fn old_coin_vsize(block: &block::Block, prevouts: &mut impl PrevoutSource) -> Result<(u64, u64)> {
+    let (mut count, mut vsize) = (0, 0);
+    for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+        for input in &tx.input {
+            let prevout = prevouts.prevout(&input.previous_output)?;
+            if block.header.time as i64 - prevout.time as i64 > YEAR_SECS {
+                count += 1;
+                vsize += tx.vsize() as u64;
+                break;
+            }
+        }
+    }
+    Ok((count, vsize))
+}
+
+pub fn block_old_coin_vsize(block_height: u64) -> Result<(u64, u64)> {
+    let block = get_block_by_height(block_height)?;
+    old_coin_vsize(&block, &mut RpcPrevouts::default())
+}
+
+/**
+ * `change` is the percent change from the previous epoch's difficulty, `None` during the first
+ * epoch
+ */
+#[derive(Debug, PartialEq)]
+pub struct SinceRetarget {
+    pub blocks_ago: u64,
+    pub difficulty: f64,
+    pub change: Option<f64>,
+}
+
+/**
+ * How far the tip is into its epoch and how the difficulty moved at the start of it.
+ * `difficulty_at` is only asked about the first block of an epoch, whose bits always carry the
+ * retargeted difficulty even on networks that allow minimum difficulty blocks
+ */
+fn since_retarget<F>(tip: u64, mut difficulty_at: F) -> Result<SinceRetarget>
+where
+    F: FnMut(u64) -> Result<f64>,
+{
+    let blocks_ago = tip % 2016;
+    let epoch_start = tip - blocks_ago;
+    let difficulty = difficulty_at(epoch_start)?;
+    let change = match epoch_start.checked_sub(2016) {
+        Some(previous_start) => percent_change(difficulty_at(previous_start)?, difficulty),
+        None => None,
+    };
+    Ok(SinceRetarget {
+        blocks_ago,
+        difficulty,
+        change,
+    })
+}
+
+pub fn last_retarget() -> Result<SinceRetarget> {
+    let rpc = &*RPC_CLIENT;
+    since_retarget(rpc.get_block_count()?, |height| {
+        let header = rpc.get_block_header(&rpc.get_block_hash(height)?)?;
+        let decoded = decode_bits(header.bits.to_consensus())
+            .map_err(|_| BitcoinRpcError::UnexpectedStructure)?;
+        Ok(decoded.difficulty)
+    })
+}
+
+/**
+ * Transaction count, size in bytes and median fee rate in sat/vB of a block, all from
+ * `getblockstats`. Like the fee rate, the size leaves out the coinbase
+ */
+fn block_size_and_median_fee_rate(block_height: u64) -> Result<(u64, f64, f64)> {
+    let rpc = &*RPC_CLIENT;
+    let stats = rpc.get_block_stats_fields(
+        block_height,
+        &[
+            BlockStatsFields::Txs,
+            BlockStatsFields::TotalSize,
+            BlockStatsFields::FeeRatePercentiles,
+        ],
+    )?;
+    let median_fee_rate = stats
+        .fee_rate_percentiles
+        .map(|percentiles| percentiles.fr_50th.to_sat())
+        .unwrap_or_default();
+    Ok((
+        stats.txs.unwrap_or_default() as u64,
+        stats.total_size.unwrap_or_default() as f64,
+        median_fee_rate as f64,
+    ))
+}
+
+/**
+ * Pearson correlation coefficient of the pairs, `None` when either side doesn't vary
+ */
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    let xs: Vec<f64> = pairs.iter().map(|(x, _)| *x).collect();
+    let ys: Vec<f64> = pairs.iter().map(|(_, y)| *y).collect();
+    let (mean_x, mean_y) = (mean(&xs)?, mean(&ys)?);
+    let (std_x, std_y) = (std_dev(&xs)?, std_dev(&ys)?);
+    if std_x == 0.0 || std_y == 0.0 {
+        return None;
+    }
+    let covariance = pairs
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum::<f64>()
+        / pairs.len() as f64;
+    Some(covariance / (std_x * std_y))
+}
+
+/**
+ * Correlation between block size and median fee rate. Coinbase-only blocks report a size and
+ * fee rate of zero, which says nothing about what full blocks paid, so they are left out
+ */
+fn size_fee_pearson(stats: &[(u64, f64, f64)]) -> Option<f64> {
+    let pairs: Vec<(f64, f64)> = stats
+        .iter()
+        .filter(|(txs, _, _)| *txs > 1)
+        .map(|(_, size, rate)| (*size, *rate))
+        .collect();
+    pearson(&pairs)
+}
+
+pub fn size_fee_correlation(start: u64, end: u64) -> Result<Option<f64>> {
+    let stats = (start..=end)
+        .map(block_size_and_median_fee_rate)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(size_fee_pearson(&stats))
+}
+
+/**
+ * Zero value OP_RETURN outputs of each block in the range
+ */
+fn data_output_counts<I>(blocks: I) -> Result<Vec<(u64, u64)>>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    blocks
+        .into_iter()
+        .map(|block| {
+            let (height, block) = block?;
+            let count = block
+                .txdata
+                .iter()
+                .flat_map(|tx| &tx.output)
+                .filter(|output| output.script_pubkey.is_op_return() && output.value.to_sat() == 0)
+                .count();
+            Ok((height, count as u64))
+        })
+        .collect()
+}
+
+/**
+ * Percent change in the average data outputs per block from the first half of the range to
+ * the second
+ */
+fn data_output_trend(counts: &[(u64, u64)]) -> Option<f64> {
+    let average = |counts: &[(u64, u64)]| {
+        let counts: Vec<f64> = counts.iter().map(|(_, count)| *count as f64).collect();
+        mean(&counts)
+    };
+    let (first, second) = counts.split_at(counts.len() / 2);
+    percent_change(average(first)?, average(second)?)
+}
+
+pub fn range_data_output_counts(start: u64, end: u64) -> Result<Vec<(u64, u64)>> {
+    data_output_counts(blocks_in_range(start, end))
+}
+
+/**
+ * Average fee rate of the block's transactions grouped by their dominant input address type,
+ * highest paying first. Transactions mostly spending bare or nonstandard scripts are left out
+ */
+fn fee_by_address_type(
+    block: &block::Block,
+    prevouts: &mut impl PrevoutSource,
+) -> Result<Vec<(ScriptType, usize, f64)>> {
+    let mut rates: HashMap<ScriptType, Vec<f64>> = HashMap::new();
+    for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+        let script_type = match dominant_input_type(tx, prevouts)? {
+            Some(
+                script_type @ (ScriptType::P2pkh
+                | ScriptType::P2sh
+                | ScriptType::P2wpkh
+                | ScriptType::P2wsh
+                | ScriptType::P2tr),
+            ) => script_type,
+            _ => continue,
+        };
+        let fee = tx_fee(tx, prevouts)?;
+        rates.entry(script_type).or_default().push(fee.fee_rate());
+    }
+
+    let mut averages: Vec<(ScriptType, usize, f64)> = rates
+        .into_iter()
+        .filter_map(|(script_type, rates)| Some((script_type, rates.len(), mean(&rates)?)))
+        .collect();
+    averages.sort_by(|a, b| b.2.total_cmp(&a.2));
+    Ok(averages)
+}
+
+pub fn block_fee_by_address_type(block_height: u64) -> Result<Vec<(ScriptType, usize, f64)>> {
+    let block = get_block_by_height(block_height)?;
+    fee_by_address_type(&block, &mut RpcPrevouts::default())
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SoloOdds {
+    pub expected_secs: f64,
+    pub expected_blocks: f64,
+    pub within_day: f64,
+    pub within_week: f64,
+}
+
+/**
+ * How long a miner hashing at `hashrate_ths` TH/s can expect to wait for a block, in seconds
+ * and in network blocks found meanwhile. Finding a block is a Poisson process, so the chance of
+ * at least one within `t` seconds is `1 - e^(-t / expected_secs)`
+ */
+fn solo_odds(difficulty: f64, hashrate_ths: f64) -> SoloOdds {
+    let expected_secs = difficulty * 2f64.powi(32) / (hashrate_ths * 1e12);
+    let within = |secs: f64| 1.0 - (-secs / expected_secs).exp();
+    SoloOdds {
+        expected_secs,
+        expected_blocks: expected_secs / 600.0,
+        within_day: within(86_400.0),
+        within_week: within(7.0 * 86_400.0),
+    }
+}
+
+pub fn current_solo_odds(hashrate_ths: f64) -> Result<SoloOdds> {
+    let rpc = &*RPC_CLIENT;
+    Ok(solo_odds(rpc.get_difficulty()?, hashrate_ths))
+}
+
+const INTERVAL_BIN_SECS: i64 = 300;
+const INTERVAL_BINS: usize = 13;
+
+/**
+ * Counts of intervals in 5 minute bins, the last bin holding everything an hour or longer
+ */
+fn interval_histogram(intervals: &[i64]) -> [u64; INTERVAL_BINS] {
+    let mut bins = [0; INTERVAL_BINS];
+    for interval in intervals {
+        let bin = ((*interval).max(0) / INTERVAL_BIN_SECS) as usize;
+        bins[bin.min(INTERVAL_BINS - 1)] += 1;
+    }
+    bins
+}
+
+/**
+ * Histogram of the interval leading up to each block of the range, the genesis block having none
+ */
+pub fn range_interval_histogram(start: u64, end: u64) -> Result<[u64; INTERVAL_BINS]> {
+    let start = start.max(1);
+    let intervals = block_intervals(&block_times(start - 1, end)?);
+    Ok(interval_histogram(&intervals))
+}
+
+/**
+ * Parses an address and checks it belongs to `network`
+ */
+fn parse_address(address: &str, network: Network) -> std::result::Result<Address, Box<dyn Error>> {
+    Ok(Address::from_str(address.trim())?.require_network(network)?)
+}
+
+/**
+ * Count and total fees of the range's transactions spending at least one output paying to
+ * `script_pubkey`. The whole fee is attributed to the address even when other inputs share it
+ */
+fn address_fees<I>(
+    blocks: I,
+    script_pubkey: &Script,
+    prevouts: &mut impl PrevoutSource,
+) -> Result<(u64, u64)>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    let (mut count, mut fees) = (0, 0);
+    for block in blocks {
+        let (_, block) = block?;
+        for tx in block.txdata.iter().filter(|tx| !tx.is_coinbase()) {
+            let mut spends_address = false;
+            for input in &tx.input {
+                if prevouts
+                    .prevout(&input.previous_output)?
+                    .txout
+                    .script_pubkey
+                    == *script_pubkey
+                {
+                    spends_address = true;
+                    break;
+                }
+            }
+            if spends_address {
+                count += 1;
+                fees += tx_fee(tx, prevouts)?.fee;
+            }
+        }
+    }
+    Ok((count, fees))
+}
+
+pub fn range_address_fees(address: &Address, start: u64, end: u64) -> Result<(u64, u64)> {
+    address_fees(
+        blocks_in_range(start, end),
+        &address.script_pubkey(),
+        &mut RpcPrevouts::default(),
+    )
+}
+
+/**
+ * Reads addresses separated by commas or newlines, skipping a header line if there is one, into
+ * the scripts they pay to
+ */
+fn load_addresses(
+    path: &Path,
+    network: Network,
+) -> std::result::Result<HashSet<ScriptBuf>, Box<dyn Error>> {
+    let mut scripts = HashSet::new();
+    for (i, line) in fs::read_to_string(path)?.lines().enumerate() {
+        for field in line
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+        {
+            match parse_address(field, network) {
+                Ok(address) => {
+                    scripts.insert(address.script_pubkey());
+                }
+                Err(_) if i == 0 => break,
+                Err(e) => return Err(format!("line {}: {}", i + 1, e).into()),
+            }
+        }
+    }
+    Ok(scripts)
+}
+
+/**
+ * Value paid to and value spent from the watched scripts over the range, in sats
+ */
+fn net_flow<I>(
+    blocks: I,
+    watched: &HashSet<ScriptBuf>,
+    prevouts: &mut impl PrevoutSource,
+) -> Result<(u64, u64)>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    let (mut received, mut sent) = (0, 0);
+    for block in blocks {
+        let (_, block) = block?;
+        for tx in &block.txdata {
+            if !tx.is_coinbase() {
+                for input in &tx.input {
+                    let prevout = prevouts.prevout(&input.previous_output)?;
+                    if watched.contains(&prevout.txout.script_pubkey) {
+                        sent += prevout.txout.value.to_sat();
+                    }
+                }
+            }
+            for output in &tx.output {
+                if watched.contains(&output.script_pubkey) {
+                    received += output.value.to_sat();
+                }
+            }
+        }
+    }
+    Ok((received, sent))
+}
+
+pub fn range_net_flow(
+    addresses_csv: &Path,
+    start: u64,
+    end: u64,
+) -> std::result::Result<(u64, u64), Box<dyn Error>> {
+    let watched = load_addresses(addresses_csv, get_chain()?)?;
+    if watched.is_empty() {
+        return Err("no addresses to watch".into());
+    }
+    Ok(net_flow(
+        blocks_in_range(start, end),
+        &watched,
+        &mut RpcPrevouts::default(),
+    )?)
+}
+
+/**
+ * Height and txid of the first output of `script_type` in the range. Blocks are only fetched up
+ * to the first match
+ */
+fn first_script_type<I>(blocks: I, script_type: ScriptType) -> Result<Option<(u64, Txid)>>
+where
+    I: IntoIterator<Item = Result<(u64, block::Block)>>,
+{
+    for block in blocks {
+        let (height, block) = block?;
+        for tx in &block.txdata {
+            if tx
+                .output
+                .iter()
+                .any(|output| ScriptType::of(&output.script_pubkey) == script_type)
+            {
+                return Ok(Some((height, tx.compute_txid())));
+            }
+        }
+    }
+    Ok(None)
+}
+
+pub fn range_first_script_type(
+    script_type: ScriptType,
+    start: u64,
+    end: u64,
+) -> Result<Option<(u64, Txid)>> {
+    first_script_type(blocks_in_range(start, end), script_type)
+}
+
+use clap::{Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(name = "bitcoin-rpc-cli")]
+#[command(version = "0.1.0")]
+#[command(about = "A CLI for the Bitcoin RPC API", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    #[command(about = "Get the current chain")]
+    Chain,
+    #[command(about = "Get the it took to mine a block")]
+    TimeToMine {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Get the number of transactions in a block")]
+    NumberOfTransactions {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Guess how long until next block is mined")]
+    NextBlock,
+    #[command(about = "Get the weight saved by the segwit discount over a range of blocks")]
+    RangeSegwitSavings {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "Find txids that appear in more than one block in a range")]
+    DuplicateTxids {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "Get the vsize weighted median fee rate of a block")]
+    WeightedMedianFee {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Get a block's coin days destroyed divided by its subsidy")]
+    CddRatio {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(
+        about = "Get the average number of blocks until a block's transactions are first spent"
+    )]
+    ChildSpacing {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+        #[arg(long, default_value_t = 144, help = "How many later blocks to scan")]
+        max_depth: u64,
+    },
+    #[command(about = "Estimate the number of payments in a block, excluding change and dust")]
+    RealPayments {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Get the average block size of each of the last completed epochs")]
+    EpochSizeTrend {
+        #[arg(
+            required = true,
+            help = "(numeric, required) How many epochs to look back"
+        )]
+        epochs: u64,
+        #[arg(
+            long,
+            default_value_t = 144,
+            help = "How many blocks to sample per epoch"
+        )]
+        samples: u64,
+    },
+    #[command(about = "Get the share of a block's fees paid by its highest fee transactions")]
+    TopFeeShare {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+        #[arg(
+            long,
+            default_value_t = 1,
+            help = "How many of the top transactions to count"
+        )]
+        top: usize,
+    },
+    #[command(about = "Decode a raw transaction hex offline")]
+    DecodeRawTx {
+        #[arg(required = true, help = "(string, required) The transaction hex")]
+        hex: String,
+    },
+    #[command(about = "Get the witness weight in a block grouped by the type of input spent")]
+    WitnessByType {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Rank how long a block took to mine against the blocks around it")]
+    IntervalRank {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) How many surrounding blocks to compare against"
+        )]
+        window: u64,
+    },
+    #[command(
+        about = "Get the ratio of OP_RETURN outputs to spendable outputs over a range of blocks"
+    )]
+    DataVsValueRatio {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(
+        about = "Get the coinbase value of each block in a range, split into subsidy and fees"
+    )]
+    CoinbaseValueTrend {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "Find outputs in a block that burn coins")]
+    BurnOutputs {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Get the gini coefficient of the fees paid in a block")]
+    FeeGini {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Get the mean and median age of the outputs spent in a block")]
+    AvgUtxoAge {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(
+        about = "Get how many transactions were included per BTC of fees over a range of blocks"
+    )]
+    TxPerFeeBtc {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "Decode a compact nBits value into its target and difficulty")]
+    DecodeBits {
+        #[arg(required = true, value_parser = parse_bits, help = "(numeric, required) The nBits value, e.g. 0x1d00ffff")]
+        bits: u32,
+    },
+    #[command(
+        about = "Estimate the share of a block's inputs that spend the owner's own recent change"
+    )]
+    SelfSpends {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+        #[arg(
+            long,
+            default_value_t = 144,
+            help = "How many blocks back a parent counts as nearby"
+        )]
+        window: u64,
+    },
+    #[command(about = "Get how many blocks' worth of transactions are waiting in the mempool")]
+    MempoolBacklog {
+        #[arg(
+            long,
+            default_value_t = 1,
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "How many blocks to get mined within"
+        )]
+        blocks: u64,
+    },
+    #[command(about = "Get the share of a block's transactions that spend a single input")]
+    SimpleSpends {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Get how many more typical transactions would have fit in a block")]
+    ThroughputCeiling {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(
+        about = "Get the number of distinct addresses paid each week over a range of blocks"
+    )]
+    AddressGrowth {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "Find transactions in a block that paid more in fees than they sent")]
+    OverpaidTxs {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Get how long miners waited past maturity to spend coinbase outputs")]
+    CoinbaseSpendMaturity {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+        #[arg(
+            long,
+            default_value_t = 2016,
+            help = "How many blocks before the range to collect coinbases from"
+        )]
+        lookback: u64,
+    },
+    #[command(
+        about = "Compare the unlocking data size of taproot key path spends against legacy spends"
+    )]
+    TaprootEfficiency {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(
+        about = "Get the number of distinct output values in a block and the most common ones"
+    )]
+    Denominations {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+        #[arg(
+            long,
+            default_value_t = 5,
+            help = "How many of the most common values to show"
+        )]
+        top: usize,
+    },
+    #[command(about = "Get the total number of transactions on chain up to a block")]
+    TxCountMilestone {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+        #[arg(long, help = "Also find the block that crossed this many transactions")]
+        milestone: Option<u64>,
+        #[arg(long, help = "File to cache partial sums in between runs")]
+        state: Option<PathBuf>,
+    },
+    #[command(about = "Get the median output value of each block in a range")]
+    MedianOutputValue {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "List the blocks in a range that took longer than a threshold to mine")]
+    SlowBlockAlerts {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+        #[arg(
+            long,
+            default_value_t = 30,
+            help = "Minutes a block may take before it counts as slow"
+        )]
+        threshold_mins: i64,
+    },
+    #[command(about = "Get the share of a block's output value paid out by the coinbase")]
+    CoinbaseValueShare {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Count the transactions in a block that use non-default sequence numbers")]
+    SequenceUsage {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Get the USD value of a block's fees from a CSV of daily prices")]
+    BlockFeeUsd {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+        #[arg(
+            required = true,
+            help = "(string, required) CSV file of date,price lines"
+        )]
+        price_csv: PathBuf,
+    },
+    #[command(about = "Get the share of blocks in a range that contain an inscription")]
+    InscriptionPrevalence {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "Get how much block sizes varied over a range of blocks")]
+    SizeVolatility {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "Get the share of a block's inputs spending outputs created the same day")]
+    SameDaySpends {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Verify the witness commitment of every block in a range")]
+    CommitmentAudit {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "Get the ratio of distinct addresses to spendable outputs in a block")]
+    AddressReuseRatio {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Get the lowest fee rate that was included in a block")]
+    InclusionFloor {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Count mempool transactions by how long they have been waiting")]
+    MempoolAges,
+    #[command(about = "Get the annualized issuance rate at a height")]
+    InflationRate {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(
+        about = "Get the share of Taproot outputs created in a range that are spent within it"
+    )]
+    TaprootChurn {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "Get the skewness of the block intervals in a range")]
+    IntervalSkew {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "Get the share of plain value transfers in a block")]
+    PlainTransfers {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(about = "Get the block space used by transactions spending coins older than a year")]
+    OldCoinVsize {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(
+        about = "Get the blocks since the last difficulty adjustment and how much it changed"
+    )]
+    SinceRetarget,
+    #[command(about = "Get the correlation between block size and median fee rate over a range")]
+    SizeFeeCorrelation {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "Count the zero value data outputs per block over a range")]
+    DataOutputTrend {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "Get the average fee rate paid by each input address type in a block")]
+    FeeByAddressType {
+        #[arg(required = true, help = "(numeric, required) The height index")]
+        block_height: u64,
+    },
+    #[command(
+        about = "Estimate the odds of a solo miner finding a block at the current difficulty"
+    )]
+    SoloOdds {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The miner's hashrate in TH/s"
+        )]
+        hashrate_ths: f64,
+    },
+    #[command(about = "Count the block intervals in a range by length")]
+    IntervalHistogram {
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(
+        about = "Get the total fees paid by transactions spending from an address over a range"
+    )]
+    AddressFees {
+        #[arg(required = true, help = "(string, required) The address")]
+        address: String,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+        #[arg(
+            long,
+            default_value_t = 1_000,
+            help = "Longest range to scan, in blocks"
+        )]
+        max_blocks: u64,
+    },
+    #[command(about = "Get the net value flowing into a set of addresses over a range")]
+    NetFlow {
+        #[arg(required = true, help = "(string, required) CSV file of addresses")]
+        addresses_csv: PathBuf,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+    #[command(about = "Find the first output of a script type in a range")]
+    FirstScriptType {
+        #[arg(
+            required = true,
+            value_name = "TYPE",
+            help = "(string, required) The script type, e.g. p2tr"
+        )]
+        script_type: ScriptType,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The first height in the range"
+        )]
+        start: u64,
+        #[arg(
+            required = true,
+            help = "(numeric, required) The last height in the range"
+        )]
+        end: u64,
+    },
+}
+
+// QUESTION: is this the best way to make error handling happen in a single place?
+// if command returns an error then return it to whatever called `call_command`
+fn call_command(command: Commands) -> std::result::Result<(), Box<dyn Error>> {
+    match command {
+        Commands::Chain => {
+            let chain = get_chain()?;
+            println!("{}", chain);
+        }
+        Commands::TimeToMine { block_height } => {
+            let time = time_to_mine(block_height)?;
+            println!("{}s, {}min", time.num_seconds(), time.num_minutes());
+        }
+        Commands::NumberOfTransactions { block_height } => {
+            let num = number_of_transactions(block_height)?;
+            println!("{} transactions", num);
+        }
+        Commands::NextBlock => {
+            println!("Next block will be mined in: ");
+            let time = guess_time_to_mine_next_block()?;
+            println!(
+                "{}s, {}min, {}days",
+                time.num_seconds(),
+                time.num_minutes(),
+                time.num_days()
+            );
+        }
+        Commands::RangeSegwitSavings { start, end } => {
+            check_range(start, end)?;
+            let savings = range_segwit_savings(start, end)?;
+            println!("{} WU saved", savings.saved_weight);
+            println!(
+                "{:.2}% of undiscounted weight",
+                savings.saved_ratio() * 100.0
+            );
+            println!(
+                "~{:.0} extra transactions of capacity",
+                savings.extra_transactions()
+            );
+        }
+        Commands::DuplicateTxids { start, end } => {
+            check_range(start, end)?;
+            let duplicates = range_duplicate_txids(start, end)?;
+            if duplicates.is_empty() {
+                println!("No duplicate txids");
+            }
+            for (txid, heights) in duplicates {
+                println!("{} at heights {:?}", txid, heights);
+            }
+        }
+        Commands::WeightedMedianFee { block_height } => match weighted_median_fee(block_height)? {
+            Some(rate) => println!("{:.2} sat/vB", rate),
+            None => println!("No transactions besides the coinbase"),
+        },
+        Commands::CddRatio { block_height } => {
+            let (cdd, ratio) = cdd_ratio(block_height)?;
+            println!("{:.2} coin days destroyed", cdd);
+            match ratio {
+                Some(_) if cdd == 0.0 => println!("No coins spent besides the coinbase"),
+                Some(ratio) => println!("{:.4} coin days per BTC of subsidy", ratio),
+                None => println!("No subsidy at this height"),
+            }
+        }
+        Commands::ChildSpacing {
+            block_height,
+            max_depth,
+        } => match block_child_spacing(block_height, max_depth)? {
+            Some(spacing) => println!("{:.2} blocks", spacing),
+            None => println!("No outputs spent within {} blocks", max_depth),
+        },
+        Commands::RealPayments { block_height } => {
+            let (count, value) = block_real_payments(block_height)?;
+            println!("{} payments, {} sats", count, value);
+        }
+        Commands::EpochSizeTrend { epochs, samples } => {
+            let averages = recent_epoch_size_trend(epochs, samples)?;
+            for (epoch, average) in &averages {
+                println!("epoch {}: {:.0} bytes", epoch, average);
+            }
+            if let (Some(first), Some(last)) = (averages.first(), averages.last()) {
+                if let Some(change) = percent_change(first.1, last.1) {
+                    println!("trend: {:+.2}%", change);
+                }
+            }
+        }
+        Commands::TopFeeShare { block_height, top } => {
+            match block_top_fee_share(block_height, top)? {
+                Some(share) => println!("top {}: {:.2}% of fees", top, share * 100.0),
+                None => println!("No fees paid"),
+            }
+        }
+        Commands::DecodeRawTx { hex } => {
+            let tx = decode_raw_tx(&hex)?;
+            println!("txid: {}", tx.compute_txid());
+            println!("wtxid: {}", tx.compute_wtxid());
+            println!("version: {}", tx.version);
+            println!("vsize: {} vB", tx.vsize());
+            println!("locktime: {}", tx.lock_time);
+            println!("inputs:");
+            for input in &tx.input {
+                println!("  {} sequence {}", input.previous_output, input.sequence);
+            }
+            println!("outputs:");
+            for output in &tx.output {
+                println!("  {} {}", output.value, output.script_pubkey);
+            }
+        }
+        Commands::WitnessByType { block_height } => {
+            for (script_type, weight) in block_witness_by_type(block_height)? {
+                println!("{:?}: {} WU", script_type, weight);
+            }
+        }
+        Commands::IntervalRank {
+            block_height,
+            window,
+        } => {
+            let (interval, rank) = interval_rank(block_height, window)?;
+            println!("{}s", interval);
+            match rank {
+                Some(rank) => println!("took longer than {:.0}% of nearby blocks", rank * 100.0),
+                None => println!("No nearby blocks to compare against"),
+            }
+        }
+        Commands::DataVsValueRatio { start, end } => {
+            check_range(start, end)?;
+            let counts = range_data_vs_value(start, end)?;
+            // undefined without spendable outputs, which shouldn't read as no data
+            let share = |data, spendable| match ratio(data, spendable) {
+                Some(share) => format!("{:.4}", share),
+                None => "n/a".to_string(),
+            };
+            for (height, data, spendable) in &counts {
+                println!(
+                    "{}: {} data, {} spendable, {}",
+                    height,
+                    data,
+                    spendable,
+                    share(*data, *spendable)
+                );
+            }
+            let data = counts.iter().map(|(_, data, _)| data).sum();
+            let spendable = counts.iter().map(|(_, _, spendable)| spendable).sum();
+            println!(
+                "total: {} data, {} spendable, {}",
+                data,
+                spendable,
+                share(data, spendable)
+            );
+        }
+        Commands::CoinbaseValueTrend { start, end } => {
+            check_range(start, end)?;
+            let values = range_coinbase_values(start, end)?;
+            for value in &values {
+                println!(
+                    "{}: {} sats ({} subsidy, {} fees)",
+                    value.height,
+                    value.total,
+                    value.subsidy,
+                    value.fees()
+                );
+            }
+            if let (Some(first), Some(last)) = (values.first(), values.last()) {
+                if let Some(change) = percent_change(first.total as f64, last.total as f64) {
+                    println!("trend: {:+.2}%", change);
+                }
+            }
+        }
+        Commands::BurnOutputs { block_height } => {
+            let burns = block_burns(block_height)?;
+            println!("{} data outputs", burns.data_outputs);
+            println!(
+                "{} outputs burning {} sats",
+                burns.value_outputs, burns.value
+            );
+        }
+        Commands::FeeGini { block_height } => match fee_gini(block_height)? {
+            Some(gini) => println!("{:.4}", gini),
+            None => println!("No fees paid"),
+        },
+        Commands::AvgUtxoAge { block_height } => match avg_utxo_age(block_height)? {
+            Some(age) => {
+                println!(
+                    "mean: {:.1} blocks, {:.2} days",
+                    age.mean_blocks, age.mean_days
+                );
+                println!(
+                    "median: {:.1} blocks, {:.2} days",
+                    age.median_blocks, age.median_days
+                );
+            }
+            None => println!("No coins spent besides the coinbase"),
+        },
+        Commands::TxPerFeeBtc { start, end } => {
+            check_range(start, end)?;
+            match range_tx_per_fee_btc(start, end)? {
+                (Some(ratio), trend) => {
+                    println!("{:.2} transactions per BTC of fees", ratio);
+                    if let Some(trend) = trend {
+                        println!("trend: {:+.2}%", trend);
+                    }
+                }
+                (None, _) => println!("No fees paid"),
+            }
+        }
+        Commands::DecodeBits { bits } => {
+            let decoded = decode_bits(bits)?;
+            println!("target: {:064x}", decoded.target);
+            println!("difficulty: {:.6e}", decoded.difficulty);
+            println!("~{:.3e} hashes to find a block", decoded.expected_hashes);
+        }
+        Commands::SelfSpends {
+            block_height,
+            window,
+        } => match block_self_spends(block_height, window)? {
+            Some(share) => println!("~{:.2}% of inputs are self spends", share * 100.0),
+            None => println!("No coins spent besides the coinbase"),
+        },
+        Commands::MempoolBacklog { blocks } => {
+            let (backlog, rate) = mempool_backlog(blocks)?;
+            if backlog == 0.0 {
+                println!("Mempool is empty");
+            } else {
+                println!("{:.2} blocks of transactions waiting", backlog);
+                match rate {
+                    Some(rate) => {
+                        println!("{:.2} sat/vB to be mined within {} blocks", rate, blocks)
+                    }
+                    None => println!("Everything fits in the next {} blocks", blocks),
+                }
+            }
+        }
+        Commands::SimpleSpends { block_height } => match block_simple_spends(block_height)? {
+            Some(share) => println!("{:.2}% single input transactions", share * 100.0),
+            None => println!("No transactions besides the coinbase"),
+        },
+        Commands::ThroughputCeiling { block_height } => {
+            let (full, extra) = block_throughput_ceiling(block_height)?;
+            println!("{:.2}% full", full * 100.0);
+            match extra {
+                Some(extra) => println!("room for {} more median sized transactions", extra),
+                None => println!("No transactions besides the coinbase"),
+            }
+        }
+        Commands::AddressGrowth { start, end } => {
+            check_range(start, end)?;
+            for week in range_address_growth(start, end)? {
+                print!(
+                    "{}-W{:02}: {} addresses",
+                    week.year, week.week, week.addresses
+                );
+                match week.growth {
+                    Some(growth) => println!(" ({:+.2}%)", growth),
+                    None => println!(),
+                }
+            }
+        }
+        Commands::OverpaidTxs { block_height } => {
+            let overpaid = block_overpaid_txs(block_height)?;
+            if overpaid.is_empty() {
+                println!("No overpaid transactions");
+            }
+            for (txid, fee, value) in overpaid {
+                println!("{}: {} sats fee, {} sats sent", txid, fee, value);
+            }
+        }
+        Commands::CoinbaseSpendMaturity {
+            start,
+            end,
+            lookback,
+        } => {
+            check_range(start, end)?;
+            match range_coinbase_spend_maturity(start, end, lookback)? {
+                Some(wait) => println!("{:.1} blocks past maturity on average", wait),
+                None => println!("No coinbase spends found"),
+            }
+        }
+        Commands::TaprootEfficiency { block_height } => {
+            let sizes = block_taproot_efficiency(block_height)?;
+            for script_type in [ScriptType::P2tr, ScriptType::P2wpkh, ScriptType::P2pkh] {
+                let size = match sizes.get(&script_type) {
+                    Some(size) => size,
+                    None => {
+                        println!("{:?}: no inputs", script_type);
+                        continue;
+                    }
+                };
+                println!(
+                    "{:?}: {} inputs, {:.1} bytes, {:.1} vB on average",
+                    script_type,
+                    size.inputs,
+                    size.avg_bytes().unwrap_or_default(),
+                    size.avg_vbytes().unwrap_or_default()
+                );
+            }
+        }
+        Commands::Denominations { block_height, top } => {
+            let (distinct, common) = block_denominations(block_height, top)?;
+            println!("{} distinct output values", distinct);
+            for (value, count) in common {
+                println!("{} sats: {} outputs", value, count);
+            }
+        }
+        Commands::TxCountMilestone {
+            block_height,
+            milestone,
+            state,
+        } => {
+            let (total, crossed) = tx_count_milestone(block_height, milestone, state.as_deref())?;
+            println!("{} transactions up to block {}", total, block_height);
+            if let Some(milestone) = milestone {
+                match crossed {
+                    Some(height) => {
+                        println!("transaction {} was mined in block {}", milestone, height)
+                    }
+                    None => println!("the chain hasn't reached {} transactions", milestone),
+                }
+            }
+        }
+        Commands::MedianOutputValue { start, end } => {
+            check_range(start, end)?;
+            let medians = range_median_output_values(start, end)?;
+            for (height, median) in &medians {
+                println!("{}: {:.0} sats", height, median.unwrap_or_default());
+            }
+            let medians: Vec<f64> = medians.iter().filter_map(|(_, median)| *median).collect();
+            if let (Some(first), Some(last)) = (medians.first(), medians.last()) {
+                if let Some(change) = percent_change(*first, *last) {
+                    println!("trend: {:+.2}%", change);
+                }
+            }
+        }
+        Commands::SlowBlockAlerts {
+            start,
+            end,
+            threshold_mins,
+        } => {
+            check_range(start, end)?;
+            let slow = slow_block_alerts(start, end, threshold_mins)?;
+            println!("{} blocks took over {}min", slow.len(), threshold_mins);
+            for (height, interval) in slow {
+                println!("{}: {}min", height, interval / 60);
+            }
+        }
+        Commands::CoinbaseValueShare { block_height } => {
+            match block_coinbase_value_share(block_height)? {
+                Some(share) => {
+                    println!("coinbase: {:.4}%", share * 100.0);
+                    println!("transactions: {:.4}%", (1.0 - share) * 100.0);
+                }
+                None => println!("No value output"),
+            }
+        }
+        Commands::SequenceUsage { block_height } => {
+            let usage = block_sequence_usage(block_height)?;
+            println!("{} relative lock time", usage.relative_lock);
+            println!("{} RBF signalling", usage.rbf);
+            println!("{} other", usage.other);
+        }
+        Commands::BlockFeeUsd {
+            block_height,
+            price_csv,
+        } => {
+            let (fees, usd) = block_fee_usd(block_height, &price_csv)?;
+            match usd {
+                Some(usd) => println!("{} sats, ${:.2}", fees, usd),
+                None => println!("{} sats, unpriced", fees),
+            }
+        }
+        Commands::InscriptionPrevalence { start, end } => {
+            check_range(start, end)?;
+            let (inscribed, total) = range_inscription_prevalence(start, end)?;
+            println!(
+                "{} of {} blocks ({:.2}%) contain inscriptions",
+                inscribed,
+                total,
+                ratio(inscribed, total).unwrap_or_default() * 100.0
+            );
+        }
+        Commands::SizeVolatility { start, end } => {
+            check_range(start, end)?;
+            if let Some(volatility) = range_size_volatility(start, end)? {
+                println!("mean: {:.0} bytes", volatility.mean);
+                println!("standard deviation: {:.0} bytes", volatility.std_dev);
+                println!(
+                    "coefficient of variation: {:.4}",
+                    volatility.coefficient_of_variation().unwrap_or_default()
+                );
+            }
+        }
+        Commands::SameDaySpends { block_height } => match block_same_day_spends(block_height)? {
+            Some(share) => println!("{:.2}% same day spends", share * 100.0),
+            None => println!("No coins spent besides the coinbase"),
+        },
+        Commands::CommitmentAudit { start, end } => {
+            check_range(start, end)?;
+            let audit = range_commitment_audit(start, end)?;
+            println!(
+                "{} blocks checked, {} without witness data skipped",
+                audit.checked, audit.skipped
+            );
+            for height in &audit.mismatched {
+                println!("{}: witness commitment mismatch", height);
+            }
+            if audit.mismatched.is_empty() {
+                println!("All witness commitments match");
+            }
+        }
+        Commands::AddressReuseRatio { block_height } => {
+            let (distinct, spendable, ratio) = block_address_reuse_ratio(block_height)?;
+            match ratio {
+                Some(ratio) => println!(
+                    "{} distinct addresses across {} spendable outputs ({:.4})",
+                    distinct, spendable, ratio
+                ),
+                None => println!("No spendable outputs"),
+            }
+        }
+        Commands::InclusionFloor { block_height } => match block_inclusion_floor(block_height)? {
+            Some(floor) => println!("{:.2} sat/vB ({})", floor.fee_rate(), floor.txid),
+            None => println!("No transactions besides the coinbase"),
+        },
+        Commands::MempoolAges => {
+            let buckets = current_mempool_ages()?;
+            for ((label, _), (count, vsize)) in MEMPOOL_AGE_BUCKETS.iter().zip(buckets) {
+                println!("{:>8}: {} transactions, {} vB", label, count, vsize);
+            }
+        }
+        Commands::InflationRate { block_height } => {
+            println!(
+                "{:.4}% per year on a supply of {} sats",
+                inflation_rate(block_height),
+                cumulative_supply(block_height)
+            );
+        }
+        Commands::TaprootChurn { start, end } => {
+            check_range(start, end)?;
+            let (created, spent) = range_taproot_churn(start, end)?;
+            match ratio(spent, created) {
+                Some(churn) => println!(
+                    "{} of {} Taproot outputs spent within the range ({:.2}%)",
+                    spent,
+                    created,
+                    churn * 100.0
+                ),
+                None => println!("No Taproot outputs created in the range"),
+            }
+        }
+        Commands::IntervalSkew { start, end } => {
+            check_range(start, end)?;
+            match interval_skew(start, end)? {
+                Some(skew) => println!("Interval skewness: {:.4}", skew),
+                None => println!("Not enough varying intervals to measure skew"),
+            }
+        }
+        Commands::PlainTransfers { block_height } => match block_plain_transfers(block_height)? {
+            Some(share) => println!("{:.2}% plain transfers", share * 100.0),
+            None => println!("No transactions besides the coinbase"),
+        },
+        Commands::OldCoinVsize { block_height } => {
+            let (count, vsize) = block_old_coin_vsize(block_height)?;
+            println!(
+                "{} transactions spending coins older than a year, {} vB",
+                count, vsize
+            );
+        }
+        Commands::SinceRetarget => {
+            let retarget = last_retarget()?;
+            println!(
+                "Last adjustment {} blocks ago, difficulty {:.2}",
+                retarget.blocks_ago, retarget.difficulty
+            );
+            match retarget.change {
+                Some(change) if change > 0.0 => println!("Increase of {:.2}%", change),
+                Some(change) if change < 0.0 => println!("Decrease of {:.2}%", -change),
+                Some(_) => println!("Unchanged from the previous epoch"),
+                None => println!("No previous epoch to compare with"),
+            }
+        }
+        Commands::SizeFeeCorrelation { start, end } => {
+            check_range(start, end)?;
+            match size_fee_correlation(start, end)? {
+                Some(correlation) => println!("Pearson correlation: {:.4}", correlation),
+                None => println!("Sizes or fee rates don't vary across the range"),
+            }
+        }
+        Commands::DataOutputTrend { start, end } => {
+            check_range(start, end)?;
+            let counts = range_data_output_counts(start, end)?;
+            for (height, count) in &counts {
+                println!("{}: {} data outputs", height, count);
+            }
+            if let Some(change) = data_output_trend(&counts) {
+                println!("trend: {:+.2}%", change);
+            }
+        }
+        Commands::FeeByAddressType { block_height } => {
+            let averages = block_fee_by_address_type(block_height)?;
+            for (script_type, count, rate) in &averages {
+                println!(
+                    "{:?}: {:.2} sat/vB over {} transactions",
+                    script_type, rate, count
+                );
+            }
+            if averages.is_empty() {
+                println!("No transactions spending standard address types");
+            }
+        }
+        Commands::SoloOdds { hashrate_ths } => {
+            if hashrate_ths <= 0.0 {
+                return Err("hashrate must be positive".into());
+            }
+            let odds = current_solo_odds(hashrate_ths)?;
+            println!(
+                "Expected to find a block every {:.1} days (~{:.0} network blocks)",
+                odds.expected_secs / 86_400.0,
+                odds.expected_blocks
+            );
+            println!("{:.6}% chance within a day", odds.within_day * 100.0);
+            println!("{:.6}% chance within a week", odds.within_week * 100.0);
+        }
+        Commands::IntervalHistogram { start, end } => {
+            check_range(start, end)?;
+            let bins = range_interval_histogram(start, end)?;
+            let bin_mins = INTERVAL_BIN_SECS / 60;
+            for (i, count) in bins.iter().enumerate() {
+                let from = i as i64 * bin_mins;
+                if i == INTERVAL_BINS - 1 {
+                    println!("{:>9}: {}", format!("{}min+", from), count);
+                } else {
+                    println!(
+                        "{:>9}: {}",
+                        format!("{}-{}min", from, from + bin_mins),
+                        count
+                    );
+                }
+            }
+        }
+        Commands::AddressFees {
+            address,
+            start,
+            end,
+            max_blocks,
+        } => {
+            check_range(start, end)?;
+            let blocks = (end - start).saturating_add(1);
+            if blocks > max_blocks {
+                return Err(format!(
+                    "range of {} blocks is longer than --max-blocks {}",
+                    blocks, max_blocks
+                )
+                .into());
+            }
+            let address = parse_address(&address, get_chain()?)?;
+            let (count, fees) = range_address_fees(&address, start, end)?;
+            println!(
+                "{} transactions spending from {} paid {} sats in fees",
+                count, address, fees
+            );
+        }
+        Commands::NetFlow {
+            addresses_csv,
+            start,
+            end,
+        } => {
+            check_range(start, end)?;
+            let (received, sent) = range_net_flow(&addresses_csv, start, end)?;
+            println!("received: {} sats", received);
+            println!("sent: {} sats", sent);
+            println!("net: {:+} sats", received as i64 - sent as i64);
+        }
+        Commands::FirstScriptType {
+            script_type,
+            start,
+            end,
+        } => {
+            check_range(start, end)?;
+            match range_first_script_type(script_type, start, end)? {
+                Some((height, txid)) => {
+                    println!("{:?} first seen at {} in {}", script_type, height, txid)
+                }
+                None => println!("No {:?} outputs in the range", script_type),
+            }
+        }
+    };
+    Ok(())
+}
+
+fn main() {
+    let cli: Cli = Cli::parse();
+
+    if let Err(e) = match cli.command {
+        Some(cmd) => call_command(cmd),
+        None => {
+            eprintln!("No command provided");
+            Ok(())
+        }
+    } {
+        eprintln!("Error: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoincore_rpc::bitcoin::{
+        absolute, hashes::Hash, Amount, PubkeyHash, ScriptHash, TxMerkleNode, WPubkeyHash,
+        WScriptHash, Witness,
+    };
+
+    #[test]
+    fn test_get_chain() {
+        // QUESTION: how to test just that this returns instance of Network? Or is that redundant because of type safety. What would be a better test if so?
+        let chain = get_chain().unwrap();
+        match chain {
+            Network::Bitcoin | Network::Regtest | Network::Signet | Network::Testnet => {
+                println!("{}", chain);
+            }
+            _ => panic!("Unexpected network type"),
+        }
+    }
+
+    #[test]
+    fn test_time_to_mine() {
+        let time = time_to_mine(24).unwrap();
+        println!("{:?}", time);
+        assert_eq!(time.num_seconds(), 666);
+    }
+
+    #[test]
+    fn test_num_transactions() {
+        let num = number_of_transactions(300_000).unwrap();
+        println!("{}", num);
+        assert_eq!(num, 237);
+    }
+
+    fn test_tx(inputs: Vec<TxIn>, outputs: Vec<TxOut>) -> Transaction {
+        Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: inputs,
+            output: outputs,
+        }
+    }
+
+    fn test_input(witness: Vec<Vec<u8>>) -> TxIn {
+        TxIn {
+            previous_output: OutPoint::new(Txid::all_zeros(), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::from_slice(&witness),
+        }
+    }
+
+    fn test_output(value: u64) -> TxOut {
+        TxOut {
+            value: Amount::from_sat(value),
+            script_pubkey: ScriptBuf::new(),
+        }
+    }
+
+    fn test_script(script_type: ScriptType) -> ScriptBuf {
+        match script_type {
+            ScriptType::P2pkh => ScriptBuf::new_p2pkh(&PubkeyHash::all_zeros()),
+            ScriptType::P2sh => ScriptBuf::new_p2sh(&ScriptHash::all_zeros()),
+            ScriptType::P2wpkh => ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()),
+            ScriptType::P2wsh => ScriptBuf::new_p2wsh(&WScriptHash::all_zeros()),
+            ScriptType::P2tr => ScriptBuf::from_bytes([vec![0x51, 0x20], vec![1; 32]].concat()),
+            ScriptType::OpReturn => ScriptBuf::new_op_return([1, 2, 3]),
+            _ => ScriptBuf::new(),
+        }
+    }
+
+    fn test_output_to(value: u64, script_type: ScriptType) -> TxOut {
+        TxOut {
+            value: Amount::from_sat(value),
+            script_pubkey: test_script(script_type),
+        }
+    }
+
+    fn test_coinbase(value: u64) -> Transaction {
+        let mut input = test_input(vec![]);
+        input.previous_output = OutPoint::null();
+        test_tx(vec![input], vec![test_output(value)])
+    }
+
+    fn test_block(txdata: Vec<Transaction>) -> block::Block {
+        block::Block {
+            header: block::Header {
+                version: block::Version::TWO,
+                prev_blockhash: BlockHash::all_zeros(),
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: 0,
+                bits: CompactTarget::from_consensus(0x1d00ffff),
+                nonce: 0,
+            },
+            txdata,
+        }
+    }
+
+    #[test]
+    fn test_range_segwit_savings() {
+        // 2 marker/flag bytes + 1 item count + (1 length + 10 bytes) = 14 witness bytes
+        let segwit = test_tx(vec![test_input(vec![vec![0; 10]])], vec![test_output(1)]);
+        let legacy = test_tx(vec![test_input(vec![])], vec![test_output(1)]);
+        assert_eq!(witness_size(&segwit), 14);
+        assert_eq!(witness_size(&legacy), 0);
+
+        let blocks = vec![
+            (1, test_block(vec![segwit.clone(), legacy.clone()])),
+            (2, test_block(vec![segwit.clone(), segwit.clone()])),
+        ];
+        let total_weight: u64 = blocks.iter().map(|(_, b)| b.weight().to_wu()).sum();
+        let savings = segwit_savings(blocks.into_iter().map(Ok)).unwrap();
+        assert_eq!(savings.transactions, 4);
+        assert_eq!(savings.saved_weight, 3 * 14 * 3);
+        assert_eq!(savings.total_weight, total_weight);
+    }
+
+    #[test]
+    fn test_duplicate_txids() {
+        let coinbase = test_tx(vec![test_input(vec![])], vec![test_output(50)]);
+        let other = test_tx(vec![test_input(vec![])], vec![test_output(25)]);
+        let blocks = vec![
+            (10, test_block(vec![coinbase.clone()])),
+            (11, test_block(vec![other.clone()])),
+            (12, test_block(vec![coinbase.clone()])),
+        ];
+        let duplicates = duplicate_txids(blocks.into_iter().map(Ok)).unwrap();
+        assert_eq!(duplicates, vec![(coinbase.compute_txid(), vec![10, 12])]);
+    }
+
+    fn test_fee(fee: u64, vsize: u64) -> TxFee {
+        TxFee {
+            txid: Txid::all_zeros(),
+            fee,
+            vsize,
+        }
+    }
+
+    fn test_prevout(value: u64, height: u64, time: u32) -> Prevout {
+        Prevout {
+            txout: test_output(value),
+            height,
+            time,
+        }
+    }
+
+    impl PrevoutSource for HashMap<OutPoint, Prevout> {
+        fn prevout(&mut self, outpoint: &OutPoint) -> Result<Prevout> {
+            self.get(outpoint)
+                .cloned()
+                .ok_or(BitcoinRpcError::UnexpectedStructure)
+        }
+    }
+
+    #[test]
+    fn test_tx_fee() {
+        let outpoint = OutPoint::new(Txid::all_zeros(), 3);
+        let mut prevouts = HashMap::from([(outpoint, test_prevout(10_000, 0, 0))]);
+        let mut input = test_input(vec![]);
+        input.previous_output = outpoint;
+        let tx = test_tx(vec![input], vec![test_output(9_000)]);
+        let fee = tx_fee(&tx, &mut prevouts).unwrap();
+        assert_eq!(fee.fee, 1_000);
+        assert_eq!(fee.vsize, tx.vsize() as u64);
+    }
+
+    #[test]
+    fn test_weighted_median_fee_rate() {
+        // the 10 sat/vB transaction is most of the vsize so it holds the median
+        let fees = vec![
+            test_fee(200, 200),
+            test_fee(6_000, 600),
+            test_fee(5_000, 100),
+        ];
+        assert_eq!(weighted_median_fee_rate(&fees), Some(10.0));
+        assert_eq!(weighted_median_fee_rate(&[]), None);
+    }
+
+    #[test]
+    fn test_cdd_ratio() {
+        let day = 86_400;
+        let outpoint = OutPoint::new(Txid::all_zeros(), 0);
+        let mut prevouts = HashMap::from([(outpoint, test_prevout(200_000_000, 0, day))]);
+        let mut input = test_input(vec![]);
+        input.previous_output = outpoint;
+
+        let coinbase = test_coinbase(312_500_000);
+        let spend = test_tx(vec![input], vec![test_output(199_000_000)]);
+        let mut block = test_block(vec![coinbase.clone(), spend]);
+        block.header.time = 11 * day;
+
+        // 2 BTC held for 10 days
+        let cdd = coin_days_destroyed(&block, &mut prevouts).unwrap();
+        assert_eq!(cdd, 20.0);
+        assert_eq!(block_subsidy(840_000), 312_500_000);
+        assert_eq!(cdd_per_subsidy(cdd, block_subsidy(840_000)), Some(6.4));
+
+        let coinbase_only = test_block(vec![coinbase]);
+        let cdd = coin_days_destroyed(&coinbase_only, &mut prevouts).unwrap();
+        assert_eq!(cdd_per_subsidy(cdd, block_subsidy(840_000)), Some(0.0));
+        assert_eq!(cdd_per_subsidy(cdd, block_subsidy(64 * 210_000)), None);
+    }
+
+    fn spending(txid: Txid) -> Transaction {
+        let mut input = test_input(vec![]);
+        input.previous_output = OutPoint::new(txid, 0);
+        test_tx(vec![input], vec![test_output(1)])
+    }
+
+    #[test]
+    fn test_child_spacing() {
+        let first = test_tx(
+            vec![test_input(vec![])],
+            vec![test_output(1), test_output(2)],
+        );
+        let second = test_tx(vec![test_input(vec![])], vec![test_output(3)]);
+        let never_spent = test_tx(vec![test_input(vec![])], vec![test_output(4)]);
+        let block = test_block(vec![
+            test_coinbase(50),
+            first.clone(),
+            second.clone(),
+            never_spent,
+        ]);
+
+        // only the first spend of `first` counts
+        let later = vec![
+            (101, test_block(vec![spending(first.compute_txid())])),
+            (102, test_block(vec![])),
+            (103, test_block(vec![spending(second.compute_txid())])),
+            (104, test_block(vec![spending(first.compute_txid())])),
+        ];
+        let spacing = child_spacing(&block, 100, later.into_iter().map(Ok)).unwrap();
+        assert_eq!(spacing, Some(2.0));
+
+        assert_eq!(child_spacing(&block, 100, vec![]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_real_payments() {
+        let outpoint = OutPoint::new(Txid::all_zeros(), 0);
+        let mut prevouts = HashMap::from([(
+            outpoint,
+            Prevout {
+                txout: test_output_to(200_000, ScriptType::P2wpkh),
+                height: 0,
+                time: 0,
+            },
+        )]);
+        let mut input = test_input(vec![]);
+        input.previous_output = outpoint;
+
+        let tx = test_tx(
+            vec![input.clone()],
+            vec![
+                test_output_to(100_000, ScriptType::P2pkh),
+                test_output_to(50_000, ScriptType::P2wpkh),
+                test_output_to(100, ScriptType::P2tr),
+                test_output_to(0, ScriptType::OpReturn),
+            ],
+        );
+        assert_eq!(change_output(&tx, &mut prevouts).unwrap(), Some(1));
+
+        let single = test_tx(
+            vec![input],
+            vec![test_output_to(20_000, ScriptType::P2wpkh)],
+        );
+        let block = test_block(vec![test_coinbase(50), tx, single]);
+        assert_eq!(real_payments(&block, &mut prevouts).unwrap(), (2, 120_000));
+    }
+
+    #[test]
+    fn test_epoch_size_trend() {
+        // epoch 1 blocks are 1000 bytes, epoch 2 alternate between 2000 and 4000
+        let size_at = |height: u64| {
+            Ok(match height / 2016 {
+                1 => 1_000,
+                _ if height.is_multiple_of(2) => 2_000,
+                _ => 4_000,
+            })
+        };
+        let averages = epoch_size_trend(1..3, 4, size_at).unwrap();
+        assert_eq!(averages, vec![(1, 1_000.0), (2, 2_000.0)]);
+        let averages = epoch_size_trend(1..3, 2016, size_at).unwrap();
+        assert_eq!(averages, vec![(1, 1_000.0), (2, 3_000.0)]);
+        assert_eq!(percent_change(1_000.0, 3_000.0), Some(200.0));
+    }
+
+    #[test]
+    fn test_top_fee_share() {
+        let fees = vec![
+            test_fee(1_000, 100),
+            test_fee(6_000, 100),
+            test_fee(3_000, 100),
+        ];
+        assert_eq!(top_fee_share(&fees, 1), Some(0.6));
+        assert_eq!(top_fee_share(&fees, 2), Some(0.9));
+        assert_eq!(top_fee_share(&fees, 10), Some(1.0));
+        assert_eq!(top_fee_share(&[test_fee(0, 100)], 1), None);
+    }
+
+    #[test]
+    fn test_decode_raw_tx() {
+        let genesis_coinbase = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
+        let tx = decode_raw_tx(genesis_coinbase).unwrap();
+        assert_eq!(
+            tx.compute_txid().to_string(),
+            "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b"
+        );
+        assert_eq!(tx.output[0].value, Amount::from_int_btc(50));
+        assert_eq!(tx.lock_time, absolute::LockTime::ZERO);
+    }
+
+    #[test]
+    fn test_decode_malformed_raw_tx() {
+        assert!(matches!(
+            decode_raw_tx("010"),
+            Err(DecodeTxError::Hex(HexToBytesError::OddLengthString(_)))
+        ));
+        assert!(matches!(
+            decode_raw_tx("0100zz"),
+            Err(DecodeTxError::Hex(HexToBytesError::InvalidChar(_)))
+        ));
+        let truncated = decode_raw_tx("01000000").unwrap_err();
+        assert!(matches!(truncated, DecodeTxError::Consensus(_)));
+        assert!(truncated.to_string().starts_with("invalid transaction: "));
+    }
+
+    #[test]
+    fn test_decode_segwit_raw_tx() {
+        let mut input = test_input(vec![vec![0; 72], vec![0; 33]]);
+        input.previous_output = OutPoint::new(Txid::all_zeros(), 1);
+        let tx = test_tx(vec![input], vec![test_output_to(1_000, ScriptType::P2wpkh)]);
+        let hex = encode::serialize_hex(&tx);
+        // 82 base bytes, then the marker, flag and witness
+        assert_eq!(hex.len(), (82 + 110) * 2);
+
+        let decoded = decode_raw_tx(&hex).unwrap();
+        assert_eq!(decoded, tx);
+        assert_ne!(
+            decoded.compute_wtxid().to_byte_array(),
+            decoded.compute_txid().to_byte_array()
+        );
+        // 82 * 4 + 110 = 438 WU
+        assert_eq!(decoded.weight().to_wu(), 438);
+        assert_eq!(decoded.vsize(), 110);
+    }
+
+    fn test_spend(prevouts: &mut HashMap<OutPoint, Prevout>, spent: Vec<TxOut>) -> Vec<TxIn> {
+        spent
+            .into_iter()
+            .map(|txout| {
+                let mut input = test_input(vec![vec![0; 10]]);
+                input.previous_output = OutPoint::new(Txid::all_zeros(), prevouts.len() as u32);
+                prevouts.insert(
+                    input.previous_output,
+                    Prevout {
+                        txout,
+                        height: 0,
+                        time: 0,
+                    },
+                );
+                input
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_witness_by_type() {
+        let mut prevouts = HashMap::new();
+        let wpkh = test_output_to(1_000, ScriptType::P2wpkh);
+        let tr = test_output_to(1_000, ScriptType::P2tr);
+
+        let inputs = test_spend(&mut prevouts, vec![wpkh.clone()]);
+        let wpkh_spend = test_tx(inputs, vec![test_output(1)]);
+        let inputs = test_spend(&mut prevouts, vec![tr.clone(), wpkh, tr.clone()]);
+        let tr_spend = test_tx(inputs, vec![test_output(1)]);
+        let inputs = test_spend(&mut prevouts, vec![tr]);
+        let other_tr_spend = test_tx(inputs, vec![test_output(1)]);
+
+        let tr_weight = witness_size(&tr_spend) + witness_size(&other_tr_spend);
+        let block = test_block(vec![
+            test_coinbase(50),
+            wpkh_spend,
+            tr_spend,
+            other_tr_spend,
+        ]);
+        assert_eq!(
+            witness_by_type(&block, &mut prevouts).unwrap(),
+            vec![
+                (ScriptType::P2tr, tr_weight as u64),
+                (ScriptType::P2wpkh, 14)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interval_rank() {
+        // the third block comes before its parent, so its interval counts as zero
+        let times = [0, 600, 500, 1_400, 1_700, 2_700, 3_000];
+        let mut intervals = block_intervals(&times);
+        assert_eq!(intervals, vec![600, 0, 900, 300, 1_000, 300]);
+
+        let own = intervals.remove(2);
+        assert_eq!(percentile_rank(own, &intervals), Some(0.8));
+        assert_eq!(percentile_rank(0, &intervals), Some(0.0));
+        assert_eq!(percentile_rank(600, &[]), None);
+
+        // the window is clamped at height 1 and at the tip
+        assert_eq!(rank_window(50, 10, 100), (45, 55));
+        assert_eq!(rank_window(2, 10, 100), (1, 7));
+        assert_eq!(rank_window(98, 10, 100), (93, 100));
+        assert_eq!(rank_window(100, 0, 100), (100, 100));
+        assert_eq!(rank_window(50, u64::MAX, 100), (1, 100));
+
+        // `times` starts at the parent of `first`
+        assert_eq!(rank_interval(&times, 1, 3), (900, Some(0.8)));
+        assert_eq!(rank_interval(&times, 1, 1), (600, Some(0.6)));
+        assert_eq!(rank_interval(&times, 1, 6), (300, Some(0.2)));
+        assert_eq!(rank_interval(&times[2..4], 3, 3), (900, None));
+
+        // rejected before the node is ever asked
+        assert!(interval_rank(0, 144).is_err());
+    }
+
+    #[test]
+    fn test_data_vs_value_ratio() {
+        let data = test_output_to(0, ScriptType::OpReturn);
+        let value = test_output_to(1_000, ScriptType::P2wpkh);
+        let tx = test_tx(
+            vec![test_input(vec![])],
+            vec![data.clone(), value.clone(), value.clone()],
+        );
+        let blocks = vec![
+            (1, test_block(vec![test_coinbase(50), tx])),
+            (
+                2,
+                test_block(vec![test_tx(vec![test_input(vec![])], vec![data])]),
+            ),
+        ];
+        let counts = data_vs_value(blocks.into_iter().map(Ok)).unwrap();
+        assert_eq!(counts, vec![(1, 1, 3), (2, 1, 0)]);
+        assert_eq!(ratio(1, 3), Some(1.0 / 3.0));
+        assert_eq!(ratio(1, 0), None);
+    }
+
+    #[test]
+    fn test_coinbase_value_trend() {
+        let blocks = vec![
+            (209_999, test_block(vec![test_coinbase(5_000_010_000)])),
+            (210_000, test_block(vec![test_coinbase(2_500_020_000)])),
+            (210_001, test_block(vec![test_coinbase(2_000_000_000)])),
+        ];
+        let values = coinbase_values(blocks.into_iter().map(Ok)).unwrap();
+        let split: Vec<(u64, u64, u64)> = values
+            .iter()
+            .map(|value| (value.total, value.subsidy, value.fees()))
+            .collect();
+        assert_eq!(
+            split,
+            vec![
+                (5_000_010_000, 5_000_000_000, 10_000),
+                (2_500_020_000, 2_500_000_000, 20_000),
+                (2_000_000_000, 2_500_000_000, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_burn_outputs() {
+        let eater = Address::from_str("1BitcoinEaterAddressDontSendf59kuE")
+            .unwrap()
+            .assume_checked();
+        let tx = test_tx(
+            vec![test_input(vec![])],
+            vec![
+                TxOut {
+                    value: Amount::from_sat(25_000),
+                    script_pubkey: eater.script_pubkey(),
+                },
+                // paying nothing to a burn address neither carries data nor burns anything
+                TxOut {
+                    value: Amount::ZERO,
+                    script_pubkey: eater.script_pubkey(),
+                },
+                test_output_to(0, ScriptType::OpReturn),
+                test_output_to(1_000, ScriptType::OpReturn),
+                test_output_to(50_000, ScriptType::P2wpkh),
+            ],
+        );
+        let block = test_block(vec![test_coinbase(50), tx]);
+        assert_eq!(
+            burns(&block),
+            Burns {
+                data_outputs: 1,
+                value_outputs: 2,
+                value: 26_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_fee_gini() {
+        assert_eq!(gini(&[1, 2, 3, 4]), Some(0.25));
+        assert_eq!(gini(&[0, 10, 0, 0]), Some(0.75));
+        assert_eq!(gini(&[5, 5]), Some(0.0));
+        assert_eq!(gini(&[7]), Some(0.0));
+        assert_eq!(gini(&[0, 0]), None);
+        assert_eq!(gini(&[]), None);
+    }
+
+    #[test]
+    fn test_avg_utxo_age() {
+        let day = 86_400;
+        let mut prevouts = HashMap::new();
+        let mut inputs = Vec::new();
+        for (vout, (height, time)) in [(900, 9 * day), (996, 13 * day), (999, 14 * day)]
+            .into_iter()
+            .enumerate()
+        {
+            let outpoint = OutPoint::new(Txid::all_zeros(), vout as u32);
+            prevouts.insert(outpoint, test_prevout(1_000, height, time));
+            let mut input = test_input(vec![]);
+            input.previous_output = outpoint;
+            inputs.push(input);
+        }
+        let mut block = test_block(vec![
+            test_coinbase(50),
+            test_tx(inputs, vec![test_output(2_000)]),
+        ]);
+        block.header.time = 15 * day;
+
+        let age = utxo_age(&block, 1_000, &mut prevouts).unwrap();
+        assert_eq!(
+            age,
+            Some(UtxoAge {
+                mean_blocks: 35.0,
+                median_blocks: 4.0,
+                mean_days: 3.0,
+                median_days: 2.0,
+            })
+        );
+
+        let coinbase_only = test_block(vec![test_coinbase(50)]);
+        assert_eq!(
+            utxo_age(&coinbase_only, 1_000, &mut prevouts).unwrap(),
+            None
+        );
+        assert_eq!(median(&[1.0, 4.0, 2.0, 3.0]), Some(2.5));
+    }
+
+    #[test]
+    fn test_tx_per_fee_btc() {
+        let stats = [
+            (1_000, 50_000_000),
+            (3_000, 50_000_000),
+            (500, 50_000_000),
+            (1_500, 50_000_000),
+        ];
+        assert_eq!(txs_per_fee_btc(&stats), Some(3_000.0));
+        // 4000 per BTC in the first half, 2000 in the second
+        assert_eq!(tx_per_fee_btc_trend(&stats), (Some(3_000.0), Some(-50.0)));
+        assert_eq!(tx_per_fee_btc_trend(&[(1, 0)]), (None, None));
+    }
+
+    #[test]
+    fn test_decode_genesis_bits() {
+        let decoded = decode_bits(0x1d00ffff).unwrap();
+        assert_eq!(
+            format!("{:064x}", decoded.target),
+            "00000000ffff0000000000000000000000000000000000000000000000000000"
+        );
+        assert_eq!(decoded.difficulty, 1.0);
+        assert_eq!(decoded.expected_hashes, 4_294_967_296.0);
+        assert_eq!(parse_bits("0x1d00ffff"), Ok(0x1d00ffff));
+        assert_eq!(parse_bits("486604799"), Ok(0x1d00ffff));
+    }
+
+    #[test]
+    fn test_decode_recent_bits() {
+        // block 840,000
+        let decoded = decode_bits(0x17034219).unwrap();
+        assert_eq!(
+            format!("{:064x}", decoded.target),
+            "0000000000000000000342190000000000000000000000000000000000000000"
+        );
+        assert!((decoded.difficulty - 86_388_558_925_171.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_decode_invalid_bits() {
+        // sign bit with a nonzero mantissa
+        assert!(decode_bits(0x01803456).is_err());
+        assert!(decode_bits(0x1d80ffff).is_err());
+        // mantissa shifted past 256 bits
+        assert!(decode_bits(0xff123456).is_err());
+        assert!(decode_bits(0x22000100).is_err());
+        assert!(decode_bits(0x21010000).is_err());
+        assert!(decode_bits(0x2100ffff).is_ok());
+        // zero targets, directly or with the mantissa shifted out
+        assert!(decode_bits(0).is_err());
+        assert!(decode_bits(0x1d000000).is_err());
+        assert!(decode_bits(0x01003456).is_err());
+
+        // regtest's minimum difficulty is well below one
+        let regtest = decode_bits(0x207fffff).unwrap();
+        assert!(regtest.difficulty > 0.0 && regtest.difficulty < 1e-9);
+        assert_eq!(format!("{:.6e}", regtest.difficulty), "4.656542e-10");
+    }
+
+    #[derive(Default)]
+    struct TestChain {
+        prevouts: HashMap<OutPoint, Prevout>,
+        txs: HashMap<Txid, Transaction>,
+    }
+
+    impl TestChain {
+        /**
+         * Records `tx` as mined at `height` and returns inputs spending each of its outputs
+         */
+        fn mine(&mut self, tx: Transaction, height: u64) -> Vec<TxIn> {
+            let txid = tx.compute_txid();
+            let mut inputs = Vec::new();
+            for (vout, txout) in tx.output.iter().enumerate() {
+                let outpoint = OutPoint::new(txid, vout as u32);
+                let prevout = Prevout {
+                    txout: txout.clone(),
+                    height,
+                    time: 0,
+                };
+                self.prevouts.insert(outpoint, prevout);
+                let mut input = test_input(vec![]);
+                input.previous_output = outpoint;
+                inputs.push(input);
+            }
+            self.txs.insert(txid, tx);
+            inputs
+        }
+    }
+
+    impl PrevoutSource for TestChain {
+        fn prevout(&mut self, outpoint: &OutPoint) -> Result<Prevout> {
+            self.prevouts.prevout(outpoint)
+        }
+    }
+
+    impl TransactionSource for TestChain {
+        fn transaction(&mut self, txid: &Txid) -> Result<Transaction> {
+            self.txs
+                .get(txid)
+                .cloned()
+                .ok_or(BitcoinRpcError::UnexpectedStructure)
+        }
+    }
+
+    #[test]
+    fn test_self_spends() {
+        let mut chain = TestChain::default();
+        let funding = test_tx(
+            vec![test_input(vec![])],
+            vec![
+                test_output_to(100_000, ScriptType::P2wpkh),
+                test_output_to(100_000, ScriptType::P2wpkh),
+            ],
+        );
+        let funded = chain.mine(funding, 10);
+
+        // pays a P2PKH output and keeps the P2WPKH output as change
+        let payment = test_tx(
+            vec![funded[0].clone()],
+            vec![
+                test_output_to(60_000, ScriptType::P2pkh),
+                test_output_to(39_000, ScriptType::P2wpkh),
+            ],
+        );
+        let recent = chain.mine(payment.clone(), 95);
+        let old_change = test_tx(vec![funded[1].clone()], payment.output.clone());
+        let old = chain.mine(old_change, 50);
+
+        let spend = test_tx(
+            vec![recent[1].clone(), recent[0].clone(), old[1].clone()],
+            vec![test_output(1)],
+        );
+        let block = test_block(vec![test_coinbase(50), spend]);
+        let share = self_spends(&block, 100, 10, &mut chain).unwrap();
+        assert_eq!(share, Some(1.0 / 3.0));
+    }
+
+    #[test]
+    fn test_mempool_backlog() {
+        assert_eq!(blocks_of_backlog(0), 0.0);
+        assert_eq!(blocks_of_backlog(2_500_000), 2.5);
+
+        let entries = [
+            (400_000, 8_000_000),
+            (500_000, 5_000_000),
+            (300_000, 1_500_000),
+            (200_000, 400_000),
+        ];
+        assert_eq!(clearing_fee_rate(&entries, 1), Some(10.0));
+        assert_eq!(clearing_fee_rate(&entries, 2), None);
+        assert_eq!(clearing_fee_rate(&[], 1), None);
+
+        // no space at all would also come back as `None`, so zero blocks is rejected up front
+        assert!(Cli::try_parse_from(["cli", "mempool-backlog", "--blocks", "0"]).is_err());
+        assert!(Cli::try_parse_from(["cli", "mempool-backlog", "--blocks", "3"]).is_ok());
+    }
+
+    #[test]
+    fn test_simple_spends() {
+        let single = test_tx(vec![test_input(vec![])], vec![test_output(1)]);
+        let batched = test_tx(
+            vec![test_input(vec![]), test_input(vec![]), test_input(vec![])],
+            vec![test_output(1)],
+        );
+        let block = test_block(vec![
+            test_coinbase(50),
+            single.clone(),
+            batched,
+            single.clone(),
+            single,
+        ]);
+        assert_eq!(simple_spends(&block), Some(0.75));
+        assert_eq!(simple_spends(&test_block(vec![test_coinbase(50)])), None);
+    }
+
+    #[test]
+    fn test_throughput_ceiling() {
+        let tx = test_tx(vec![test_input(vec![])], vec![test_output(1)]);
+        let tx_weight = tx.weight().to_wu();
+        let block = test_block(vec![test_coinbase(50), tx.clone(), tx.clone(), tx]);
+        let weight = block.weight().to_wu();
+
+        assert_eq!(median_tx_weight(&block), Some(tx_weight as f64));
+        let (full, extra) = throughput_ceiling(&block);
+        assert_eq!(full, weight as f64 / 4_000_000.0);
+        assert_eq!(extra, Some((4_000_000 - weight) / tx_weight));
+        assert_eq!(
+            throughput_ceiling(&test_block(vec![test_coinbase(50)])).1,
+            None
+        );
+    }
+
+    fn test_address_output(n: u8) -> TxOut {
+        TxOut {
+            value: Amount::from_sat(1_000),
+            script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([n; 20])),
+        }
+    }
+
+    #[test]
+    fn test_address_growth() {
+        let day = 86_400;
+        // 2024-01-01 is the Monday starting ISO week 1
+        let monday = 1_704_067_200;
+        let paying = |addresses: &[u8], time: u32| {
+            let outputs = addresses.iter().map(|n| test_address_output(*n)).collect();
+            let mut block = test_block(vec![test_tx(vec![test_input(vec![])], outputs)]);
+            block.header.time = time;
+            block
+        };
+        let blocks = vec![
+            (1, paying(&[1, 2], monday)),
+            (2, paying(&[2, 3], monday + day)),
+            (3, paying(&[1, 2, 3, 4, 5, 6], monday + 7 * day)),
+            // timestamped back in week 2 while week 3 is open
+            (4, paying(&[7], monday + 14 * day)),
+            (5, paying(&[8, 9], monday + 13 * day)),
+        ];
+        let weeks = address_growth(blocks.into_iter().map(Ok), Network::Bitcoin).unwrap();
+        let counts: Vec<(u32, u64, Option<f64>)> = weeks
+            .iter()
+            .map(|week| (week.week, week.addresses, week.growth))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![(1, 3, None), (2, 6, Some(100.0)), (3, 3, Some(-50.0))]
+        );
+    }
+
+    #[test]
+    fn test_overpaid_txs() {
+        let mut chain = TestChain::default();
+        let funding = test_tx(
+            vec![test_input(vec![])],
+            vec![test_output(100_000), test_output(100_000)],
+        );
+        let funded = chain.mine(funding, 1);
+
+        let overpaid = test_tx(vec![funded[0].clone()], vec![test_output(30_000)]);
+        let normal = test_tx(vec![funded[1].clone()], vec![test_output(99_000)]);
+        let block = test_block(vec![test_coinbase(50), overpaid.clone(), normal]);
+        assert_eq!(
+            overpaid_txs(&block, &mut chain).unwrap(),
+            vec![(overpaid.compute_txid(), 70_000, 30_000)]
+        );
+    }
+
+    #[test]
+    fn test_coinbase_spend_maturity() {
+        let early = test_coinbase(50);
+        let in_range = test_coinbase(25);
+        let coinbases = HashMap::from([(early.compute_txid(), 800)]);
+        let blocks = vec![
+            (1_000, test_block(vec![in_range.clone()])),
+            (
+                1_050,
+                test_block(vec![test_coinbase(1), spending(early.compute_txid())]),
+            ),
+            (
+                1_110,
+                test_block(vec![test_coinbase(2), spending(in_range.compute_txid())]),
+            ),
+        ];
+        // 150 and 10 blocks past maturity
+        let wait = coinbase_spend_maturity(coinbases, blocks.into_iter().map(Ok)).unwrap();
+        assert_eq!(wait, Some(80.0));
+    }
+
+    #[test]
+    fn test_taproot_efficiency() {
+        let mut chain = TestChain::default();
+        let funding = test_tx(
+            vec![test_input(vec![])],
+            vec![
+                test_output_to(10_000, ScriptType::P2tr),
+                test_output_to(10_000, ScriptType::P2wpkh),
+            ],
+        );
+        let mut funded = chain.mine(funding, 1);
+        funded[0].witness = Witness::from_slice(&[vec![0; 64]]);
+        funded[1].witness = Witness::from_slice(&[vec![0; 72], vec![0; 33]]);
+
+        let spend = test_tx(funded, vec![test_output(19_000)]);
+        let block = test_block(vec![test_coinbase(50), spend]);
+        let sizes = taproot_efficiency(&block, &mut chain).unwrap();
+
+        // item count, then a length prefix for every element
+        let taproot = &sizes[&ScriptType::P2tr];
+        assert_eq!(taproot.avg_bytes(), Some(66.0));
+        assert_eq!(taproot.avg_vbytes(), Some(16.5));
+        let wpkh = &sizes[&ScriptType::P2wpkh];
+        assert_eq!(wpkh.avg_bytes(), Some(108.0));
+        assert!(!sizes.contains_key(&ScriptType::P2pkh));
+
+        let legacy_funding = test_tx(
+            vec![test_input(vec![])],
+            vec![test_output_to(10_000, ScriptType::P2pkh)],
+        );
+        let mut legacy_funded = chain.mine(legacy_funding, 2);
+        // a 72 byte signature and 33 byte pubkey, each behind a one byte push
+        legacy_funded[0].script_sig = ScriptBuf::from_bytes(vec![0; 107]);
+        let legacy = test_tx(legacy_funded, vec![test_output(9_000)]);
+        let block = test_block(vec![test_coinbase(50), legacy]);
+        let sizes = taproot_efficiency(&block, &mut chain).unwrap();
+        let pkh = &sizes[&ScriptType::P2pkh];
+        assert_eq!(pkh.avg_bytes(), Some(107.0));
+        assert_eq!(pkh.avg_vbytes(), Some(107.0));
+    }
+
+    #[test]
+    fn test_denominations() {
+        let coinjoin = test_tx(
+            vec![test_input(vec![])],
+            vec![
+                test_output(100_000),
+                test_output(100_000),
+                test_output(100_000),
+                test_output(5_000),
+                test_output_to(0, ScriptType::OpReturn),
+            ],
+        );
+        let payment = test_tx(
+            vec![test_input(vec![])],
+            vec![test_output(5_000), test_output(1_234)],
+        );
+        let block = test_block(vec![test_coinbase(50), coinjoin, payment]);
+        assert_eq!(
+            denominations(&block, 2),
+            (4, vec![(100_000, 3), (5_000, 2)])
+        );
+    }
+
+    #[test]
+    fn test_tx_count_milestone() {
+        // block h has h + 1 transactions, so the totals run 1, 3, 6, 10, 15, ...
+        let mut counted = Vec::new();
+        let mut tx_count = |height: u64| {
+            counted.push(height);
+            Ok(height + 1)
+        };
+        let mut cache = TxCountCache::default();
+        assert_eq!(cache.cumulative(4, &mut tx_count).unwrap(), 15);
+        assert_eq!(cache.cumulative(6, &mut tx_count).unwrap(), 28);
+        assert_eq!(cache.milestone(10, 6, &mut tx_count).unwrap(), Some(3));
+        assert_eq!(cache.milestone(30, 6, &mut tx_count).unwrap(), None);
+        // the second sum resumed from the checkpoint at 4, the first milestone search had no
+        // checkpoint below 10 so it started over
+        assert_eq!(counted, vec![0, 1, 2, 3, 4, 5, 6, 0, 1, 2, 3]);
+
+        let path = env::temp_dir().join("test_tx_count_milestone.txt");
+        cache.save(&path).unwrap();
+        assert_eq!(TxCountCache::load(&path).unwrap(), cache);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_median_output_value() {
+        let data = test_output_to(0, ScriptType::OpReturn);
+        let paying = |values: &[u64]| {
+            let mut outputs: Vec<TxOut> = values.iter().map(|value| test_output(*value)).collect();
+            outputs.push(data.clone());
+            test_tx(vec![test_input(vec![])], outputs)
+        };
+        let blocks = vec![
+            (1, test_block(vec![test_coinbase(50), paying(&[10, 1_000])])),
+            (
+                2,
+                test_block(vec![test_coinbase(50), paying(&[30, 70, 90_000])]),
+            ),
+            (3, test_block(vec![paying(&[])])),
+        ];
+        let medians = median_output_values(blocks.into_iter().map(Ok)).unwrap();
+        assert_eq!(medians, vec![(1, Some(50.0)), (2, Some(60.0)), (3, None)]);
+    }
+
+    #[test]
+    fn test_slow_block_alerts() {
+        // block 103 is timestamped before its parent, which must not hide or fake a slow block
+        let times = [0, 600, 1_200, 3_600, 3_000, 3_300];
+        assert_eq!(slow_blocks(100, &times, 30), vec![(102, 2_400)]);
+        assert_eq!(slow_blocks(100, &times, 60), vec![]);
+    }
+
+    #[test]
+    fn test_coinbase_value_share() {
+        let tx = test_tx(
+            vec![test_input(vec![])],
+            vec![test_output(600_000_000), test_output(100_000_000)],
+        );
+        let block = test_block(vec![test_coinbase(300_000_000), tx]);
+        assert_eq!(coinbase_value_share(&block), Some(0.3));
+        let empty = test_block(vec![test_coinbase(0)]);
+        assert_eq!(coinbase_value_share(&empty), None);
+    }
+
+    #[test]
+    fn test_sequence_usage() {
+        let with_sequences = |version, sequences: &[u32]| {
+            let inputs = sequences
+                .iter()
+                .map(|sequence| {
+                    let mut input = test_input(vec![]);
+                    input.sequence = Sequence(*sequence);
+                    input
+                })
+                .collect();
+            let mut tx = test_tx(inputs, vec![test_output(1)]);
+            tx.version = version;
+            tx
+        };
+        let v1 = transaction::Version::ONE;
+        let v2 = transaction::Version::TWO;
+        let block = test_block(vec![
+            test_coinbase(50),
+            with_sequences(v2, &[0xffffffff, 144]),
+            with_sequences(v2, &[0xfffffffd]),
+            // relative lock times aren't enforced for version 1
+            with_sequences(v1, &[144]),
+            with_sequences(v2, &[0xfffffffe]),
+            with_sequences(v2, &[0xffffffff, 0xffffffff]),
+        ]);
+        assert_eq!(
+            sequence_usage(&block),
+            SequenceUsage {
+                relative_lock: 1,
+                rbf: 2,
+                other: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_block_fee_usd() {
+        let path = env::temp_dir().join("test_block_fee_usd.csv");
+        fs::write(&path, "date,price\n2024-04-19,63000\n2024-04-22,66000\n").unwrap();
+        let prices = load_prices(&path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        // a block mined on 2024-04-20 paying 37.626948 BTC in fees
+        let fees = 3_762_694_800;
+        let time = 1_713_571_767;
+        assert_eq!(fees_usd(fees, time, &prices), Some(37.626948 * 63_000.0));
+        assert_eq!(
+            fees_usd(fees, time + 2 * 86_400, &prices),
+            Some(37.626948 * 66_000.0)
+        );
+        assert_eq!(fees_usd(fees, time + 3 * 86_400, &prices), None);
+        assert_eq!(fees_usd(fees, time - 2 * 86_400, &prices), None);
+    }
+
+    fn test_inscription() -> Transaction {
+        // signature, tapscript with the envelope, control block
+        let mut tapscript = vec![0x20; 33];
+        tapscript.extend([0xac, 0x00, 0x63, 0x03, b'o', b'r', b'd', 0x01, 0x01]);
+        tapscript.extend(b"text/plain");
+        tapscript.push(0x68);
+        test_tx(
+            vec![test_input(vec![vec![0; 64], tapscript, vec![0xc0; 33]])],
+            vec![test_output(546)],
+        )
+    }
+
+    #[test]
+    fn test_inscription_prevalence() {
+        let plain = test_tx(vec![test_input(vec![vec![0; 64]])], vec![test_output(1)]);
+        assert!(has_inscription(&test_inscription()));
+        assert!(!has_inscription(&plain));
+
+        let blocks = vec![
+            (1, test_block(vec![test_coinbase(50), test_inscription()])),
+            (2, test_block(vec![test_coinbase(50), plain.clone()])),
+            (3, test_block(vec![test_coinbase(50)])),
+            (
+                4,
+                test_block(vec![test_coinbase(50), plain, test_inscription()]),
+            ),
+        ];
+        let prevalence = inscription_prevalence(blocks.into_iter().map(Ok)).unwrap();
+        assert_eq!(prevalence, (2, 4));
+        assert_eq!(ratio(prevalence.0, prevalence.1), Some(0.5));
+    }
+
+    #[test]
+    fn test_size_volatility() {
+        let sized = |outputs: usize| {
+            let tx = test_tx(vec![test_input(vec![])], vec![test_output(1); outputs]);
+            test_block(vec![tx])
+        };
+        let blocks = vec![(1, sized(1)), (2, sized(5)), (3, sized(9))];
+        let sizes: Vec<f64> = blocks.iter().map(|(_, b)| b.total_size() as f64).collect();
+        // every extra output adds the same number of bytes
+        let step = sizes[1] - sizes[0];
+        assert_eq!(sizes[2] - sizes[1], step);
+
+        let volatility = size_volatility(blocks.into_iter().map(Ok))
+            .unwrap()
+            .unwrap();
+        assert_eq!(volatility.mean, sizes[1]);
+        assert!((volatility.std_dev - step * (2.0f64 / 3.0).sqrt()).abs() < 1e-9);
+        let cv = volatility.coefficient_of_variation().unwrap();
+        assert!((cv - volatility.std_dev / sizes[1]).abs() < 1e-9);
+        assert_eq!(size_volatility(vec![]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_same_day_spends() {
+        // 2024-01-01 12:00 UTC
+        let noon = 1_704_110_400;
+        let hour = 3_600;
+        let mut prevouts = HashMap::new();
+        let mut inputs = Vec::new();
+        for (vout, time) in [
+            noon - hour,
+            noon - 12 * hour,
+            noon - 13 * hour,
+            noon - 30 * hour,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let outpoint = OutPoint::new(Txid::all_zeros(), vout as u32);
+            prevouts.insert(outpoint, test_prevout(1_000, 0, time));
+            let mut input = test_input(vec![]);
+            input.previous_output = outpoint;
+            inputs.push(input);
+        }
+        let mut block = test_block(vec![
+            test_coinbase(50),
+            test_tx(inputs, vec![test_output(1)]),
+        ]);
+        block.header.time = noon;
+
+        // midnight and an hour before it fall either side of the date change
+        assert_eq!(same_day_spends(&block, &mut prevouts).unwrap(), Some(0.5));
+    }
+
+    fn test_segwit_block() -> block::Block {
+        let mut coinbase = test_coinbase(50);
+        coinbase.input[0].witness = Witness::from_slice(&[[0u8; 32]]);
+        let spend = test_tx(vec![test_input(vec![vec![1; 64]])], vec![test_output(1)]);
+        let mut block = test_block(vec![coinbase, spend]);
+
+        let commitment =
+            block::Block::compute_witness_commitment(&block.witness_root().unwrap(), &[0; 32]);
+        let mut script = vec![0x6a, 0x24, 0xaa, 0x21, 0xa9, 0xed];
+        script.extend(commitment.to_byte_array());
+        block.txdata[0].output.push(TxOut {
+            value: Amount::ZERO,
+            script_pubkey: ScriptBuf::from_bytes(script),
+        });
+        block
+    }
+
+    #[test]
+    fn test_commitment_audit() {
+        let mut tampered = test_segwit_block();
+        tampered.txdata[1].input[0].witness = Witness::from_slice(&[vec![2; 64]]);
+        let blocks = vec![
+            (1, test_block(vec![test_coinbase(50)])),
+            (2, test_segwit_block()),
+            (3, tampered),
+        ];
+        assert_eq!(
+            commitment_audit(blocks.into_iter().map(Ok)).unwrap(),
+            CommitmentAudit {
+                checked: 2,
+                skipped: 1,
+                mismatched: vec![3],
+            }
+        );
+    }
+
+    #[test]
+    fn test_address_reuse_ratio() {
+        let block = test_block(vec![
+            test_tx(vec![], vec![test_address_output(1), test_address_output(2)]),
+            test_tx(
+                vec![],
+                vec![
+                    test_address_output(1),
+                    test_address_output(1),
+                    test_output_to(0, ScriptType::OpReturn),
+                ],
+            ),
+        ]);
+        assert_eq!(
+            address_reuse_ratio(&block, Network::Bitcoin),
+            (2, 4, Some(0.5))
+        );
+        assert_eq!(
+            address_reuse_ratio(&test_block(vec![]), Network::Bitcoin),
+            (0, 0, None)
+        );
+    }
+
+    #[test]
+    fn test_inclusion_floor() {
+        let mut chain = TestChain::default();
+        let funding = test_tx(
+            vec![test_input(vec![])],
+            vec![test_output(100_000), test_output(100_000)],
+        );
+        let funded = chain.mine(funding, 1);
+
+        let cheap = test_tx(vec![funded[0].clone()], vec![test_output(99_900)]);
+        let pricey = test_tx(vec![funded[1].clone()], vec![test_output(90_000)]);
+        let block = test_block(vec![test_coinbase(50), pricey, cheap.clone()]);
+        let floor = inclusion_floor(&block, &mut chain).unwrap().unwrap();
+        assert_eq!(floor.txid, cheap.compute_txid());
+        assert_eq!(floor.fee_rate(), 100.0 / cheap.vsize() as f64);
+
+        let empty = test_block(vec![test_coinbase(50)]);
+        assert!(inclusion_floor(&empty, &mut chain).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_mempool_ages() {
+        let now = 1_000_000;
+        let entries = vec![
+            (now + 30, 100),
+            (now - 599, 200),
+            (now - 600, 300),
+            (now - 7_200, 400),
+            (now - 30_000, 500),
+            (now - 86_400, 600),
+            (now - 200_000, 700),
+        ];
+        assert_eq!(
+            mempool_ages(&entries, now),
+            [(2, 300), (1, 300), (1, 400), (1, 500), (2, 1_300)]
+        );
+    }
+
+    #[test]
+    fn test_inflation_rate() {
+        assert_eq!(cumulative_supply(0), 5_000_000_000);
+        assert_eq!(cumulative_supply(209_999), 210_000 * 5_000_000_000);
+        assert_eq!(
+            cumulative_supply(210_000),
+            210_000 * 5_000_000_000 + 2_500_000_000
+        );
+        assert_eq!(cumulative_supply(10_000_000), cumulative_supply(6_930_000));
+        assert!(cumulative_supply(10_000_000) < 21_000_000 * 100_000_000);
+
+        // first block of the fourth halving era, ~0.83%
+        assert!((inflation_rate(840_000) - 0.8343).abs() < 0.0001);
+        assert_eq!(inflation_rate(10_000_000), 0.0);
+    }
+
+    #[test]
+    fn test_taproot_churn() {
+        let funding = test_tx(
+            vec![test_input(vec![])],
+            vec![
+                test_output_to(1_000, ScriptType::P2tr),
+                test_output_to(2_000, ScriptType::P2tr),
+                test_output_to(3_000, ScriptType::P2wpkh),
+            ],
+        );
+        let funding_txid = funding.compute_txid();
+        let mut spend = test_tx(
+            vec![test_input(vec![]), test_input(vec![])],
+            vec![test_output_to(500, ScriptType::P2tr)],
+        );
+        spend.input[0].previous_output = OutPoint::new(funding_txid, 0);
+        spend.input[1].previous_output = OutPoint::new(funding_txid, 2);
+        let respend = spending(spend.compute_txid());
+
+        let blocks = vec![
+            (1, test_block(vec![test_coinbase(50), funding])),
+            (2, test_block(vec![test_coinbase(50), spend, respend])),
+        ];
+        let (created, spent) = taproot_churn(blocks.into_iter().map(Ok)).unwrap();
+        assert_eq!((created, spent), (3, 2));
+        assert_eq!(ratio(spent, created), Some(2.0 / 3.0));
+    }
+
+    #[test]
+    fn test_skewness() {
+        // the last block's timestamp is behind its parent's, clamping that interval to zero
+        let times = [0, 60, 120, 180, 780, 700];
+        let intervals: Vec<f64> = block_intervals(&times)
+            .into_iter()
+            .map(|interval| interval as f64)
+            .collect();
+        assert_eq!(intervals, vec![60.0, 60.0, 60.0, 600.0, 0.0]);
+
+        let skew = skewness(&[60.0, 60.0, 60.0, 600.0]).unwrap();
+        assert!((skew - 2.0 / 3f64.sqrt()).abs() < 1e-9);
+        assert!(skewness(&intervals).unwrap() > 0.0);
+        assert_eq!(skewness(&[600.0, 600.0]), None);
+        assert_eq!(skewness(&[]), None);
+    }
+
+    #[test]
+    fn test_plain_transfers() {
+        let mut chain = TestChain::default();
+        let funding = test_tx(
+            vec![test_input(vec![])],
+            vec![
+                test_output_to(10_000, ScriptType::P2wpkh),
+                test_output_to(10_000, ScriptType::P2wpkh),
+                test_output_to(10_000, ScriptType::P2wpkh),
+                test_output_to(10_000, ScriptType::P2tr),
+            ],
+        );
+        let funded = chain.mine(funding, 1);
+
+        let plain = test_tx(
+            vec![funded[0].clone()],
+            vec![test_output_to(9_000, ScriptType::P2wpkh)],
+        );
+        let data = test_tx(
+            vec![funded[1].clone()],
+            vec![
+                test_output_to(9_000, ScriptType::P2wpkh),
+                test_output_to(0, ScriptType::OpReturn),
+            ],
+        );
+        let mut locked = test_tx(
+            vec![funded[2].clone()],
+            vec![test_output_to(9_000, ScriptType::P2wpkh)],
+        );
+        locked.lock_time = absolute::LockTime::from_height(800_000).unwrap();
+        let mixed = test_tx(
+            vec![funded[3].clone()],
+            vec![test_output_to(9_000, ScriptType::P2wpkh)],
+        );
+
+        let block = test_block(vec![test_coinbase(50), plain, data, locked, mixed]);
+        assert_eq!(plain_transfers(&block, &mut chain).unwrap(), Some(0.25));
+        let empty = test_block(vec![test_coinbase(50)]);
+        assert_eq!(plain_transfers(&empty, &mut chain).unwrap(), None);
+    }
+
+    #[test]
+    fn test_old_coin_vsize() {
+        let day = 86_400;
+        let mut prevouts = HashMap::new();
+        let old_inputs = test_spend(&mut prevouts, vec![test_output(1_000), test_output(2_000)]);
+        let recent_inputs = test_spend(&mut prevouts, vec![test_output(3_000)]);
+        for (vout, time) in [(1, 390 * day), (2, 399 * day)] {
+            let outpoint = OutPoint::new(Txid::all_zeros(), vout);
+            prevouts.get_mut(&outpoint).unwrap().time = time;
+        }
+
+        let old = test_tx(old_inputs, vec![test_output(2_900)]);
+        let recent = test_tx(recent_inputs, vec![test_output(2_900)]);
+        let mut block = test_block(vec![test_coinbase(50), old.clone(), recent]);
+        block.header.time = 400 * day;
+        assert_eq!(
+            old_coin_vsize(&block, &mut prevouts).unwrap(),
+            (1, old.vsize() as u64)
+        );
+    }
+
+    #[test]
+    fn test_since_retarget() {
+        let difficulty_at = |height: u64| {
+            assert!(height.is_multiple_of(2016));
+            Ok(match height / 2016 {
+                0 => 1.0,
+                1 => 2.0,
+                _ => 1.5,
+            })
+        };
+        assert_eq!(
+            since_retarget(2016 + 10, difficulty_at).unwrap(),
+            SinceRetarget {
+                blocks_ago: 10,
+                difficulty: 2.0,
+                change: Some(100.0),
+            }
+        );
+        let falling = since_retarget(2 * 2016, difficulty_at).unwrap();
+        assert_eq!((falling.blocks_ago, falling.change), (0, Some(-25.0)));
+        assert_eq!(since_retarget(2015, difficulty_at).unwrap().change, None);
+    }
+
+    #[test]
+    fn test_pearson() {
+        let fuller_pays_more = [
+            (1_000_000.0, 10.0),
+            (1_200_000.0, 13.0),
+            (1_400_000.0, 13.0),
+            (1_600_000.0, 18.0),
+            (1_800_000.0, 20.0),
+        ];
+        let correlation = pearson(&fuller_pays_more).unwrap();
+        assert!(correlation > 0.9 && correlation < 1.0);
+
+        let exact: Vec<(f64, f64)> = (0..5).map(|x| (x as f64, 10.0 - 2.0 * x as f64)).collect();
+        assert!((pearson(&exact).unwrap() + 1.0).abs() < 1e-9);
+
+        assert_eq!(pearson(&[(1.0, 5.0), (2.0, 5.0)]), None);
+        assert_eq!(pearson(&[]), None);
+    }
+
+    #[test]
+    fn test_size_fee_pearson() {
+        let stats = [
+            (2_000, 1_000_000.0, 10.0),
+            (2_500, 1_200_000.0, 13.0),
+            (3_000, 1_400_000.0, 13.0),
+            (3_500, 1_600_000.0, 18.0),
+        ];
+        let correlation = size_fee_pearson(&stats).unwrap();
+
+        let mut with_empty = stats.to_vec();
+        with_empty.insert(2, (1, 0.0, 0.0));
+        assert_eq!(size_fee_pearson(&with_empty), Some(correlation));
+        let pairs: Vec<(f64, f64)> = with_empty.iter().map(|(_, x, y)| (*x, *y)).collect();
+        assert!(pearson(&pairs).unwrap() > correlation);
+
+        assert_eq!(size_fee_pearson(&[(1, 0.0, 0.0), (1, 0.0, 0.0)]), None);
+    }
+
+    #[test]
+    fn test_data_output_trend() {
+        let data = || test_output_to(0, ScriptType::OpReturn);
+        let blocks = vec![
+            (1, test_block(vec![test_coinbase(50)])),
+            (
+                2,
+                test_block(vec![
+                    test_coinbase(50),
+                    // OP_RETURN outputs burning value aren't counted
+                    test_tx(
+                        vec![],
+                        vec![data(), test_output_to(10, ScriptType::OpReturn)],
+                    ),
+                ]),
+            ),
+            (
+                3,
+                test_block(vec![test_tx(vec![], vec![data(), data(), test_output(1)])]),
+            ),
+            (4, test_block(vec![test_tx(vec![], vec![data(); 4])])),
+        ];
+        let counts = data_output_counts(blocks.into_iter().map(Ok)).unwrap();
+        assert_eq!(counts, vec![(1, 0), (2, 1), (3, 2), (4, 4)]);
+        assert_eq!(data_output_trend(&counts), Some(500.0));
+        assert_eq!(data_output_trend(&counts[..2]), None);
+    }
+
+    #[test]
+    fn test_fee_by_address_type() {
+        let mut chain = TestChain::default();
+        let funding = test_tx(
+            vec![test_input(vec![])],
+            vec![
+                test_output_to(100_000, ScriptType::P2wpkh),
+                test_output_to(100_000, ScriptType::P2wpkh),
+                test_output_to(100_000, ScriptType::P2tr),
+                test_output_to(100_000, ScriptType::Other),
+            ],
+        );
+        let funded = chain.mine(funding, 1);
+        let spend =
+            |input: &TxIn, fee: u64| test_tx(vec![input.clone()], vec![test_output(100_000 - fee)]);
+
+        let wpkh_cheap = spend(&funded[0], 1_000);
+        let wpkh_pricey = spend(&funded[1], 3_000);
+        let tr = spend(&funded[2], 10_000);
+        let vsize = tr.vsize() as f64;
+        let block = test_block(vec![
+            test_coinbase(50),
+            wpkh_cheap,
+            wpkh_pricey,
+            tr,
+            spend(&funded[3], 50_000),
+        ]);
+        assert_eq!(
+            fee_by_address_type(&block, &mut chain).unwrap(),
+            vec![
+                (ScriptType::P2tr, 1, 10_000.0 / vsize),
+                (
+                    ScriptType::P2wpkh,
+                    2,
+                    (1_000.0 / vsize + 3_000.0 / vsize) / 2.0
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_solo_odds() {
+        // 1 TH/s at this difficulty expects one block a day
+        let difficulty = 86_400.0 * 1e12 / 2f64.powi(32);
+        let odds = solo_odds(difficulty, 1.0);
+        assert!((odds.expected_secs - 86_400.0).abs() < 1e-6);
+        assert!((odds.expected_blocks - 144.0).abs() < 1e-9);
+        assert!((odds.within_day - (1.0 - (-1f64).exp())).abs() < 1e-9);
+        assert!((odds.within_week - (1.0 - (-7f64).exp())).abs() < 1e-9);
+
+        let doubled = solo_odds(difficulty, 2.0);
+        assert!((doubled.expected_secs - 43_200.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_interval_histogram() {
+        // a timestamp behind its parent's gives a zero interval
+        let times = [0, 120, 420, 720, 1_320, 1_300, 5_500, 9_100];
+        let intervals = block_intervals(&times);
+        assert_eq!(intervals, vec![120, 300, 300, 600, 0, 4_200, 3_600]);
+        assert_eq!(
+            interval_histogram(&intervals),
+            [2, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2]
+        );
+        assert_eq!(interval_histogram(&[]), [0; INTERVAL_BINS]);
+    }
+
+    #[test]
+    fn test_address_fees() {
+        let watched = test_address_output(7);
+        let mut chain = TestChain::default();
+        let funding = test_tx(
+            vec![test_input(vec![])],
+            vec![watched.clone(), watched.clone(), test_address_output(8)],
+        );
+        let funded = chain.mine(funding, 1);
+        let spend =
+            |input: &TxIn, fee: u64| test_tx(vec![input.clone()], vec![test_output(1_000 - fee)]);
+
+        let blocks = vec![
+            (
+                2,
+                test_block(vec![
+                    test_coinbase(50),
+                    spend(&funded[0], 500),
+                    spend(&funded[2], 300),
+                ]),
+            ),
+            (3, test_block(vec![test_coinbase(50)])),
+            (4, test_block(vec![spend(&funded[1], 200)])),
+        ];
+        assert_eq!(
+            address_fees(
+                blocks.into_iter().map(Ok),
+                &watched.script_pubkey,
+                &mut chain
+            )
+            .unwrap(),
+            (2, 700)
+        );
+    }
+
+    #[test]
+    fn test_parse_address() {
+        let address = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+        assert!(parse_address(address, Network::Bitcoin).is_ok());
+        assert!(parse_address(address, Network::Testnet).is_err());
+        assert!(parse_address("not an address", Network::Bitcoin).is_err());
+    }
+
+    #[test]
+    fn test_net_flow() {
+        let path = env::temp_dir().join("test_net_flow.csv");
+        fs::write(
+            &path,
+            "address\nbc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq,\n\n1BitcoinEaterAddressDontSendf59kuE\n",
+        )
+        .unwrap();
+        let watched = load_addresses(&path, Network::Bitcoin).unwrap();
+        fs::write(&path, "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq\nnope\n").unwrap();
+        assert!(load_addresses(&path, Network::Bitcoin).is_err());
+        fs::remove_file(path).unwrap();
+        assert_eq!(watched.len(), 2);
+
+        let mut watched_outputs = watched.iter().map(|script_pubkey| TxOut {
+            value: Amount::ZERO,
+            script_pubkey: script_pubkey.clone(),
+        });
+        let paying = |mut output: TxOut, value: u64| {
+            output.value = Amount::from_sat(value);
+            output
+        };
+        let (first, second) = (
+            watched_outputs.next().unwrap(),
+            watched_outputs.next().unwrap(),
+        );
+
+        let mut chain = TestChain::default();
+        let mut coinbase = test_coinbase(500);
+        coinbase.output.push(paying(first, 1_000));
+        coinbase.output.push(paying(second.clone(), 2_000));
+        let funded = chain.mine(coinbase.clone(), 1);
+
+        let sending = test_tx(vec![funded[1].clone()], vec![test_output(900)]);
+        let receiving = test_tx(vec![funded[0].clone()], vec![paying(second, 400)]);
+        let blocks = vec![
+            (1, test_block(vec![coinbase])),
+            (2, test_block(vec![sending, receiving])),
+        ];
+        assert_eq!(
+            net_flow(blocks.into_iter().map(Ok), &watched, &mut chain).unwrap(),
+            (3_400, 1_000)
+        );
+    }
+
+    #[test]
+    fn test_first_script_type() {
+        assert_eq!("P2TR".parse(), Ok(ScriptType::P2tr));
+        assert_eq!("op_return".parse(), Ok(ScriptType::OpReturn));
+        assert!("p2foo".parse::<ScriptType>().is_err());
+
+        let taproot = test_tx(vec![], vec![test_output_to(1, ScriptType::P2tr)]);
+        let blocks = vec![
+            Ok((1, test_block(vec![test_coinbase(50)]))),
+            Ok((
+                2,
+                test_block(vec![test_coinbase(50), taproot.clone(), taproot.clone()]),
+            )),
+            // never reached, the scan stops at the first match
+            Err(BitcoinRpcError::UnexpectedStructure),
+        ];
+        assert_eq!(
+            first_script_type(blocks, ScriptType::P2tr).unwrap(),
+            Some((2, taproot.compute_txid()))
+        );
+        let blocks = vec![Ok((1, test_block(vec![test_coinbase(50)])))];
+        assert_eq!(first_script_type(blocks, ScriptType::P2wsh).unwrap(), None);
     }
 }